@@ -0,0 +1,47 @@
+// 只记录在内存中的自启动状态，用于测试依赖自启动的逻辑
+
+use super::{AutostartBackend, AutostartCommand};
+use crate::error::AppError;
+use std::sync::Mutex;
+
+#[derive(Default)]
+pub struct MemoryAutostart {
+    normal: Mutex<Option<AutostartCommand>>,
+    admin: Mutex<Option<AutostartCommand>>,
+}
+
+impl MemoryAutostart {
+    // 当前注册的普通自启动命令
+    pub fn normal(&self) -> Option<AutostartCommand> {
+        self.normal.lock().unwrap().clone()
+    }
+
+    // 当前注册的管理员自启动命令
+    pub fn admin(&self) -> Option<AutostartCommand> {
+        self.admin.lock().unwrap().clone()
+    }
+}
+
+fn set(slot: &Mutex<Option<AutostartCommand>>, command: &AutostartCommand, enabled: bool) {
+    *slot.lock().unwrap() = enabled.then(|| command.clone());
+}
+
+impl AutostartBackend for MemoryAutostart {
+    fn set_normal(&self, command: &AutostartCommand, enabled: bool) -> Result<(), AppError> {
+        set(&self.normal, command, enabled);
+        Ok(())
+    }
+
+    fn is_normal_enabled(&self) -> Result<bool, AppError> {
+        Ok(self.normal.lock().unwrap().is_some())
+    }
+
+    fn set_admin(&self, command: &AutostartCommand, enabled: bool) -> Result<(), AppError> {
+        set(&self.admin, command, enabled);
+        Ok(())
+    }
+
+    fn is_admin_enabled(&self) -> Result<bool, AppError> {
+        Ok(self.admin.lock().unwrap().is_some())
+    }
+}
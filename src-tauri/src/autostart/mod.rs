@@ -0,0 +1,54 @@
+// EasiStartup自身的开机自启
+//
+// 普通自启动在用户登录时以普通权限运行，管理员自启动在登录时以最高权限运行。
+// 实现在编译时按平台选择：Windows使用注册表Run键和计划任务，Linux使用XDG autostart，
+// 启用systemd特性时改为systemd用户服务。
+
+use crate::error::AppError;
+use crate::task_scheduler::TaskInfo;
+
+// 测试中代替平台实现
+#[cfg(test)]
+pub mod memory;
+#[cfg(windows)]
+mod windows;
+#[cfg(all(not(windows), feature = "systemd"))]
+pub mod systemd;
+#[cfg(all(not(windows), not(feature = "systemd")))]
+mod xdg;
+
+// 自启动时执行的命令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutostartCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+pub trait AutostartBackend {
+    // 设置或取消普通自启动，取消时command不会被使用
+    fn set_normal(&self, command: &AutostartCommand, enabled: bool) -> Result<(), AppError>;
+    fn is_normal_enabled(&self) -> Result<bool, AppError>;
+    // 设置或取消管理员自启动
+    fn set_admin(&self, command: &AutostartCommand, enabled: bool) -> Result<(), AppError>;
+    fn is_admin_enabled(&self) -> Result<bool, AppError>;
+    // 管理员自启动计划任务的定义和运行状态，任务不存在或平台不使用计划任务时为None
+    fn admin_task(&self) -> Result<Option<TaskInfo>, AppError> {
+        Ok(None)
+    }
+}
+
+// 当前平台的实现
+#[cfg(windows)]
+pub fn platform() -> impl AutostartBackend {
+    windows::WindowsAutostart::new(crate::runner::SystemRunner)
+}
+
+#[cfg(all(not(windows), not(feature = "systemd")))]
+pub fn platform() -> impl AutostartBackend {
+    xdg::XdgAutostart::new()
+}
+
+#[cfg(all(not(windows), feature = "systemd"))]
+pub fn platform() -> systemd::SystemdAutostart<crate::runner::SystemRunner> {
+    systemd::SystemdAutostart::new(crate::runner::SystemRunner)
+}
@@ -0,0 +1,708 @@
+// Linux：以systemd用户服务自启动
+//
+// EasiStartup自身注册为 easistartup.service；也可以为每个启用的启动项生成
+// easistartup-item-<ID>.service，由systemd负责延迟、依赖和重启，不再经过EasiStartup。
+// 单元文件写入 ~/.config/systemd/user，通过 systemctl --user 启用和禁用。
+
+use super::{AutostartBackend, AutostartCommand};
+use crate::error::AppError;
+use crate::i18n::{tr, trf, Message};
+use crate::retry::Backoff;
+use crate::runner::CommandRunner;
+use crate::supervisor::{RestartMode, RestartPolicy};
+use crate::{storage, Launch, StartupItem};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const SERVICE: &str = "easistartup.service";
+const ITEM_PREFIX: &str = "easistartup-item-";
+// 图形界面程序需要在桌面会话启动后运行
+const TARGET: &str = "graphical-session.target";
+
+// 命令行中为启动项安装或删除单元的参数
+pub const ITEMS_ARG: &str = "--systemd-items";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemsAction {
+    Install,
+    Remove,
+}
+
+// 解析 --systemd-items install|remove，没有该参数时返回None
+pub fn parse_cli(args: &[String]) -> Result<Option<ItemsAction>, String> {
+    let Some(index) = args.iter().position(|arg| arg == ITEMS_ARG) else {
+        return Ok(None);
+    };
+    match args.get(index + 1).map(String::as_str) {
+        Some("install") => Ok(Some(ItemsAction::Install)),
+        Some("remove") => Ok(Some(ItemsAction::Remove)),
+        _ => Err(format!("{} requires install or remove", ITEMS_ARG)),
+    }
+}
+
+pub struct SystemdAutostart<R> {
+    runner: R,
+    // 无法确定用户目录时为None
+    unit_dir: Option<PathBuf>,
+}
+
+// $XDG_CONFIG_HOME/systemd/user，未设置时为 ~/.config/systemd/user
+fn user_unit_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("systemd").join("user"))
+}
+
+impl<R: CommandRunner> SystemdAutostart<R> {
+    pub fn new(runner: R) -> SystemdAutostart<R> {
+        SystemdAutostart { runner, unit_dir: user_unit_dir() }
+    }
+
+    fn unit_dir(&self) -> Result<&PathBuf, AppError> {
+        self.unit_dir
+            .as_ref()
+            .ok_or_else(|| AppError::NotFound(tr(Message::AutostartDirNotFound).to_string()))
+    }
+
+    // 执行 systemctl --user，退出码非0时返回错误
+    fn systemctl(&self, args: &[&str]) -> Result<(), AppError> {
+        let mut full_args = vec!["--user"];
+        full_args.extend_from_slice(args);
+        let output = self
+            .runner
+            .run("systemctl", &full_args, None)
+            .map_err(|e| AppError::Systemd(trf(Message::SystemctlSpawnFailed, &[&e])))?;
+        if !output.success() {
+            return Err(AppError::Systemd(trf(Message::SystemctlFailed, &[&args.join(" "), &output.stderr.trim()])));
+        }
+        Ok(())
+    }
+
+    // 单元是否已启用，systemctl is-enabled对未启用或不存在的单元返回非0
+    fn is_enabled(&self, unit: &str) -> Result<bool, AppError> {
+        let output = self
+            .runner
+            .run("systemctl", &["--user", "is-enabled", unit], None)
+            .map_err(|e| AppError::Systemd(trf(Message::SystemctlSpawnFailed, &[&e])))?;
+        Ok(output.success())
+    }
+
+    fn write_unit(&self, name: &str, content: &str) -> Result<(), AppError> {
+        let dir = self.unit_dir()?;
+        fs::create_dir_all(dir).map_err(|e| AppError::io(Message::WriteUnitFile, e))?;
+        storage::write_atomic(&dir.join(name), content.as_bytes())
+            .map_err(|e| AppError::io(Message::WriteUnitFile, e))
+    }
+
+    // 删除单元文件，返回文件是否存在
+    fn remove_unit(&self, name: &str) -> Result<bool, AppError> {
+        match fs::remove_file(self.unit_dir()?.join(name)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(AppError::io(Message::RemoveUnitFile, e)),
+        }
+    }
+
+    // 已安装的启动项单元文件名
+    fn installed_item_units(&self) -> Result<Vec<String>, AppError> {
+        let read_dir = match fs::read_dir(self.unit_dir()?) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::io(Message::RemoveUnitFile, e)),
+        };
+        Ok(read_dir
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with(ITEM_PREFIX))
+            .collect())
+    }
+
+    // 为启用的启动项安装并启用单元，同时删除已不存在或已禁用的启动项的单元，返回安装的启动项数量
+    //
+    // 需要管理员权限的启动项不能作为用户服务运行，会被跳过。EasiStartup自身仍然自启动时，
+    // 这些启动项会被启动两次，应先关闭EasiStartup的自启动。
+    pub fn install_items(&self, items: &[StartupItem]) -> Result<usize, AppError> {
+        let mut units = Vec::new();
+        for item in items.iter().filter(|item| item.enabled) {
+            if item.run_as_admin {
+                log::warn!("Skipping startup item '{}': it requires administrator privileges", item.name);
+                continue;
+            }
+            units.extend(item_units(item, items));
+        }
+
+        self.remove_item_units(|name| units.iter().all(|(unit, _)| unit != name))?;
+        for (name, content) in &units {
+            self.write_unit(name, content)?;
+        }
+        self.systemctl(&["daemon-reload"])?;
+
+        // 有延迟的启动项由定时器启动，其余的随桌面会话启动
+        let entry_points: Vec<&str> = units
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| {
+                name.ends_with(".timer") || !units.iter().any(|(other, _)| *other == name.replace(".service", ".timer"))
+            })
+            .collect();
+        if !entry_points.is_empty() {
+            let mut args = vec!["enable"];
+            args.extend(entry_points.iter().copied());
+            self.systemctl(&args)?;
+        }
+
+        Ok(units.iter().filter(|(name, _)| name.ends_with(".service")).count())
+    }
+
+    // 禁用并删除所有启动项的单元，返回删除的单元数量
+    pub fn remove_items(&self) -> Result<usize, AppError> {
+        let removed = self.remove_item_units(|_| true)?;
+        self.systemctl(&["daemon-reload"])?;
+        Ok(removed)
+    }
+
+    fn remove_item_units(&self, should_remove: impl Fn(&str) -> bool) -> Result<usize, AppError> {
+        let stale: Vec<String> = self.installed_item_units()?.into_iter().filter(|name| should_remove(name)).collect();
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        let mut args = vec!["disable"];
+        args.extend(stale.iter().map(String::as_str));
+        // 单元可能从未启用过，禁用失败不影响删除
+        if let Err(e) = self.systemctl(&args) {
+            log::warn!("Failed to disable startup item units: {}", e);
+        }
+        for name in &stale {
+            self.remove_unit(name)?;
+        }
+        Ok(stale.len())
+    }
+}
+
+impl<R: CommandRunner> AutostartBackend for SystemdAutostart<R> {
+    fn set_normal(&self, command: &AutostartCommand, enabled: bool) -> Result<(), AppError> {
+        if enabled {
+            self.write_unit(SERVICE, &self_unit(command))?;
+            self.systemctl(&["daemon-reload"])?;
+            // 只启用不立即启动，当前已经有一个EasiStartup在运行
+            self.systemctl(&["enable", SERVICE])
+        } else {
+            if self.unit_dir()?.join(SERVICE).exists() {
+                self.systemctl(&["disable", SERVICE])?;
+            }
+            if self.remove_unit(SERVICE)? {
+                self.systemctl(&["daemon-reload"])?;
+            }
+            Ok(())
+        }
+    }
+
+    fn is_normal_enabled(&self) -> Result<bool, AppError> {
+        self.is_enabled(SERVICE)
+    }
+
+    fn set_admin(&self, _command: &AutostartCommand, enabled: bool) -> Result<(), AppError> {
+        if enabled {
+            return Err(AppError::Unsupported(tr(Message::AdminAutostartUnsupported).to_string()));
+        }
+        Ok(())
+    }
+
+    fn is_admin_enabled(&self) -> Result<bool, AppError> {
+        Ok(false)
+    }
+}
+
+// 引用ExecStart等命令行中的参数；%和$分别是说明符和环境变量的前缀
+fn quote_exec(arg: &str) -> String {
+    quote(arg).replace('$', "$$")
+}
+
+// 引用取值，包含空白、引号或反斜杠时加双引号；%是说明符的前缀
+fn quote(value: &str) -> String {
+    let value = value.replace('%', "%%");
+    if !value.is_empty() && !value.contains([' ', '\t', '\n', '"', '\'', '\\', ';']) {
+        return value;
+    }
+
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn exec_line<S: AsRef<str>>(program: &str, args: &[S]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().map(AsRef::as_ref))
+        .map(quote_exec)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// EasiStartup自身的服务
+fn self_unit(command: &AutostartCommand) -> String {
+    format!(
+        "[Unit]\n\
+         Description=EasiStartup\n\
+         After={target}\n\
+         PartOf={target}\n\
+         \n\
+         [Service]\n\
+         ExecStart={exec}\n\
+         \n\
+         [Install]\n\
+         WantedBy={target}\n",
+        target = TARGET,
+        exec = exec_line(&command.program, &command.args),
+    )
+}
+
+// 单元名中只保留字母、数字、-和_
+fn item_unit_name(id: &str, suffix: &str) -> String {
+    let id: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}{}.{}", ITEM_PREFIX, id, suffix)
+}
+
+// 启动项的服务单元，有延迟时另外生成同名的定时器
+fn item_units(item: &StartupItem, items: &[StartupItem]) -> Vec<(String, String)> {
+    let expanded;
+    let item = if item.expand_environment {
+        expanded = item.with_expanded_environment();
+        &expanded
+    } else {
+        item
+    };
+
+    let mut unit = String::new();
+    let _ = writeln!(unit, "[Unit]");
+    let _ = writeln!(unit, "Description={} (EasiStartup)", item.name.replace('%', "%%"));
+    let _ = writeln!(unit, "After={}", TARGET);
+    let _ = writeln!(unit, "PartOf={}", TARGET);
+    // 依赖的启动项先启动；已禁用或不存在的依赖不生成单元，不能引用
+    for dependency in &item.depends_on {
+        if items.iter().any(|other| &other.id == dependency && other.enabled && !other.run_as_admin) {
+            let name = item_unit_name(dependency, "service");
+            let _ = writeln!(unit, "Wants={}", name);
+            let _ = writeln!(unit, "After={}", name);
+        }
+    }
+    if item.restart.is_enabled() {
+        // 初次启动也计入次数
+        let _ = writeln!(unit, "StartLimitIntervalSec={}", item.restart.window_seconds);
+        let _ = writeln!(unit, "StartLimitBurst={}", item.restart.max_restarts + 1);
+    }
+
+    let _ = writeln!(unit, "\n[Service]");
+    let exec = match &item.launch {
+        Launch::Executable { path, .. } => exec_line(path, &item.argv()),
+        Launch::Shell { command, shell } => exec_line(shell.program(), &shell.arguments(command)),
+    };
+    let _ = writeln!(unit, "ExecStart={}", exec);
+    if let Some(dir) = item.working_dir() {
+        let _ = writeln!(unit, "WorkingDirectory={}", quote(&dir.to_string_lossy()));
+    }
+    for (name, value) in &item.environment {
+        match value {
+            Some(value) => {
+                let _ = writeln!(unit, "Environment={}", quote(&format!("{}={}", name, value)));
+            }
+            None => {
+                let _ = writeln!(unit, "UnsetEnvironment={}", quote(name));
+            }
+        }
+    }
+    write_restart(&mut unit, &item.restart);
+
+    let service = item_unit_name(&item.id, "service");
+    let delay = item.delay().as_secs();
+    if delay == 0 {
+        let _ = write!(unit, "\n[Install]\nWantedBy={}\n", TARGET);
+        return vec![(service, unit)];
+    }
+
+    // 定时器随桌面会话启动，OnActiveSec从定时器启动时开始计时
+    let timer = format!(
+        "[Unit]\n\
+         Description=Delayed start of {name} (EasiStartup)\n\
+         PartOf={target}\n\
+         \n\
+         [Timer]\n\
+         OnActiveSec={delay}s\n\
+         AccuracySec=1s\n\
+         Unit={service}\n\
+         \n\
+         [Install]\n\
+         WantedBy={target}\n",
+        name = item.name.replace('%', "%%"),
+        target = TARGET,
+        delay = delay,
+        service = service,
+    );
+    vec![(service.clone(), unit), (item_unit_name(&item.id, "timer"), timer)]
+}
+
+// 保活策略对应的Restart=等设置
+fn write_restart(unit: &mut String, policy: &RestartPolicy) {
+    let restart = match policy.mode {
+        RestartMode::Never => "no",
+        RestartMode::OnFailure => "on-failure",
+        RestartMode::Always => "always",
+    };
+    let _ = writeln!(unit, "Restart={}", restart);
+    if !policy.is_enabled() {
+        return;
+    }
+
+    let _ = writeln!(unit, "RestartSec={}s", policy.delay_seconds.max(1));
+    // 指数退避：每次等待时间翻倍，直到上限（systemd 254及以上版本支持）
+    if policy.backoff == Backoff::Exponential && policy.max_delay_seconds > policy.delay_seconds {
+        let mut steps = 0;
+        let mut delay = policy.delay_seconds.max(1);
+        while delay < policy.max_delay_seconds {
+            delay = delay.saturating_mul(2);
+            steps += 1;
+        }
+        let _ = writeln!(unit, "RestartSteps={}", steps);
+        let _ = writeln!(unit, "RestartMaxDelaySec={}s", policy.max_delay_seconds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::recording::RecordingRunner;
+    use crate::runner::Output;
+    use crate::shell::Shell;
+    use std::collections::BTreeMap;
+
+    const SELF_UNIT: &str = include_str!("../../tests/fixtures/systemd/easistartup.service");
+    const ITEM_SERVICE: &str = include_str!("../../tests/fixtures/systemd/item.service");
+    const ITEM_TIMER: &str = include_str!("../../tests/fixtures/systemd/item.timer");
+
+    // 测试结束时删除的临时目录
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> TempDir {
+            let dir = std::env::temp_dir().join(format!("easistartup-systemd-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn backend(runner: RecordingRunner, dir: &TempDir) -> SystemdAutostart<RecordingRunner> {
+        SystemdAutostart { runner, unit_dir: Some(dir.0.clone()) }
+    }
+
+    fn item(id: &str) -> StartupItem {
+        StartupItem {
+            id: id.to_string(),
+            name: id.to_string(),
+            launch: Launch::Executable { path: format!("/usr/bin/{}", id), arguments: String::new(), args: None },
+            ..StartupItem::default()
+        }
+    }
+
+    fn unit_files(dir: &TempDir) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(&dir.0)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn quote_leaves_plain_values_alone() {
+        assert_eq!(quote("/usr/bin/app"), "/usr/bin/app");
+        assert_eq!(quote("--name=value"), "--name=value");
+    }
+
+    #[test]
+    fn quote_escapes_special_characters() {
+        assert_eq!(quote(""), "\"\"");
+        assert_eq!(quote("my files"), "\"my files\"");
+        assert_eq!(quote("50%"), "50%%");
+        assert_eq!(quote("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote("C:\\dir"), "\"C:\\\\dir\"");
+        assert_eq!(quote("it's"), "\"it's\"");
+        assert_eq!(quote("a;b"), "\"a;b\"");
+        assert_eq!(quote("line1\nline2"), "\"line1\\nline2\"");
+    }
+
+    #[test]
+    fn quote_exec_escapes_dollar_signs() {
+        assert_eq!(quote_exec("$HOME"), "$$HOME");
+        assert_eq!(quote_exec("${HOME}/a b"), "\"$${HOME}/a b\"");
+        assert_eq!(quote_exec("100%"), "100%%");
+        assert_eq!(exec_line("/bin/echo", &["a b", "$x"]), "/bin/echo \"a b\" $$x");
+    }
+
+    #[test]
+    fn self_unit_matches_golden_text() {
+        let command = AutostartCommand {
+            program: "/opt/Easi Startup/easistartup".to_string(),
+            args: vec!["--autostart".to_string()],
+        };
+        assert_eq!(self_unit(&command), SELF_UNIT);
+    }
+
+    #[test]
+    fn item_unit_names_replace_unsafe_characters() {
+        assert_eq!(item_unit_name("a1-b_2", "service"), "easistartup-item-a1-b_2.service");
+        assert_eq!(item_unit_name("a b/c.d", "timer"), "easistartup-item-a_b_c_d.timer");
+    }
+
+    #[test]
+    fn delayed_item_matches_golden_units() {
+        let sync = StartupItem {
+            name: "同步 50%".to_string(),
+            launch: Launch::Executable {
+                path: "/usr/bin/rsync".to_string(),
+                arguments: String::new(),
+                args: Some(vec!["-a".to_string(), "$HOME/src".to_string(), "/mnt/backup dir".to_string()]),
+            },
+            working_directory: "/home/user/my files".to_string(),
+            environment: BTreeMap::from([
+                ("LANG".to_string(), Some("zh_CN.UTF-8".to_string())),
+                ("GREETING".to_string(), Some("hello \"world\"".to_string())),
+                ("PROXY".to_string(), None),
+            ]),
+            delay_enabled: true,
+            delay_seconds: 30,
+            restart: RestartPolicy {
+                mode: RestartMode::OnFailure,
+                max_restarts: 3,
+                window_seconds: 120,
+                backoff: Backoff::Exponential,
+                delay_seconds: 2,
+                max_delay_seconds: 60,
+            },
+            // 只有db会生成单元，其余依赖不能引用
+            depends_on: ["db", "disabled", "admin", "missing"].iter().map(|id| id.to_string()).collect(),
+            ..item("sync-1")
+        };
+        let items = vec![
+            sync.clone(),
+            item("db"),
+            StartupItem { enabled: false, ..item("disabled") },
+            StartupItem { run_as_admin: true, ..item("admin") },
+        ];
+
+        let units = item_units(&sync, &items);
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[0].0, "easistartup-item-sync-1.service");
+        assert_eq!(units[0].1, ITEM_SERVICE);
+        assert_eq!(units[1].0, "easistartup-item-sync-1.timer");
+        assert_eq!(units[1].1, ITEM_TIMER);
+    }
+
+    #[test]
+    fn undelayed_item_is_installed_with_the_session() {
+        let echo = StartupItem {
+            launch: Launch::Shell { command: "echo 100% > /tmp/$USER".to_string(), shell: Shell::Sh },
+            delay_enabled: false,
+            ..item("echo")
+        };
+
+        let units = item_units(&echo, std::slice::from_ref(&echo));
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].0, "easistartup-item-echo.service");
+        let unit = &units[0].1;
+        assert!(unit.contains("ExecStart=sh -c \"echo 100%% > /tmp/$$USER\"\n"), "{}", unit);
+        assert!(unit.contains("Restart=no\n"), "{}", unit);
+        assert!(!unit.contains("StartLimit"), "{}", unit);
+        assert!(!unit.contains("Wants="), "{}", unit);
+        assert!(unit.ends_with("\n[Install]\nWantedBy=graphical-session.target\n"), "{}", unit);
+    }
+
+    #[test]
+    fn environment_is_expanded_when_requested() {
+        let app = StartupItem {
+            launch: Launch::Executable { path: "${APP_HOME}/bin/app".to_string(), arguments: String::new(), args: None },
+            environment: BTreeMap::from([("APP_HOME".to_string(), Some("/opt/app".to_string()))]),
+            expand_environment: true,
+            ..item("app")
+        };
+
+        let units = item_units(&app, std::slice::from_ref(&app));
+        assert!(units[0].1.contains("ExecStart=/opt/app/bin/app\n"), "{}", units[0].1);
+    }
+
+    fn restart_lines(policy: RestartPolicy) -> String {
+        let mut unit = String::new();
+        write_restart(&mut unit, &policy);
+        unit
+    }
+
+    #[test]
+    fn restart_never_only_disables_restarts() {
+        assert_eq!(restart_lines(RestartPolicy::default()), "Restart=no\n");
+    }
+
+    #[test]
+    fn restart_exponential_backoff_counts_doublings() {
+        let policy = RestartPolicy {
+            mode: RestartMode::Always,
+            delay_seconds: 0,
+            max_delay_seconds: 10,
+            ..RestartPolicy::default()
+        };
+        // 1、2、4、8、16，翻倍4次后超过上限
+        assert_eq!(
+            restart_lines(policy),
+            "Restart=always\nRestartSec=1s\nRestartSteps=4\nRestartMaxDelaySec=10s\n"
+        );
+    }
+
+    #[test]
+    fn restart_without_growth_has_no_steps() {
+        let fixed = RestartPolicy {
+            mode: RestartMode::OnFailure,
+            backoff: Backoff::Fixed,
+            delay_seconds: 5,
+            ..RestartPolicy::default()
+        };
+        assert_eq!(restart_lines(fixed), "Restart=on-failure\nRestartSec=5s\n");
+
+        // 上限不大于初始等待时间时不会增长
+        let capped = RestartPolicy { mode: RestartMode::Always, delay_seconds: 30, max_delay_seconds: 30, ..RestartPolicy::default() };
+        assert_eq!(restart_lines(capped), "Restart=always\nRestartSec=30s\n");
+    }
+
+    #[test]
+    fn install_items_replaces_stale_units_and_enables_entry_points() {
+        let dir = TempDir::new();
+        fs::write(dir.0.join("easistartup-item-old.service"), "").unwrap();
+        fs::write(dir.0.join(SERVICE), "").unwrap();
+        let systemd = backend(RecordingRunner::new(), &dir);
+
+        let items = vec![
+            item("a"),
+            StartupItem { delay_enabled: true, delay_seconds: 10, ..item("b") },
+            StartupItem { enabled: false, ..item("c") },
+            StartupItem { run_as_admin: true, ..item("d") },
+        ];
+
+        assert_eq!(systemd.install_items(&items).unwrap(), 2);
+        assert_eq!(
+            systemd.runner.calls(),
+            vec![
+                "systemctl --user disable easistartup-item-old.service",
+                "systemctl --user daemon-reload",
+                "systemctl --user enable easistartup-item-a.service easistartup-item-b.timer",
+            ]
+        );
+        assert_eq!(
+            unit_files(&dir),
+            vec![
+                "easistartup-item-a.service",
+                "easistartup-item-b.service",
+                "easistartup-item-b.timer",
+                "easistartup.service",
+            ]
+        );
+    }
+
+    #[test]
+    fn install_items_reports_systemctl_failures() {
+        let dir = TempDir::new();
+        let runner = RecordingRunner::responding(|_, args| match args {
+            ["--user", "enable", ..] => Output { code: Some(1), stdout: String::new(), stderr: "Failed to enable\n".to_string() },
+            _ => Output { code: Some(0), ..Output::default() },
+        });
+        let systemd = backend(runner, &dir);
+
+        assert!(matches!(systemd.install_items(&[item("a")]), Err(AppError::Systemd(_))));
+    }
+
+    #[test]
+    fn remove_items_disables_and_deletes_item_units() {
+        let dir = TempDir::new();
+        for name in ["easistartup-item-a.service", "easistartup-item-b.timer", SERVICE] {
+            fs::write(dir.0.join(name), "").unwrap();
+        }
+        // 单元从未启用时禁用会失败，不影响删除
+        let runner = RecordingRunner::responding(|_, args| match args {
+            ["--user", "disable", ..] => Output { code: Some(1), ..Output::default() },
+            _ => Output { code: Some(0), ..Output::default() },
+        });
+        let systemd = backend(runner, &dir);
+
+        assert_eq!(systemd.remove_items().unwrap(), 2);
+        let calls = systemd.runner.calls();
+        assert_eq!(calls.len(), 2);
+        let mut disabled: Vec<&str> = calls[0].strip_prefix("systemctl --user disable ").unwrap().split(' ').collect();
+        disabled.sort();
+        assert_eq!(disabled, vec!["easistartup-item-a.service", "easistartup-item-b.timer"]);
+        assert_eq!(calls[1], "systemctl --user daemon-reload");
+        assert_eq!(unit_files(&dir), vec![SERVICE]);
+    }
+
+    #[test]
+    fn remove_items_without_units_only_reloads() {
+        let dir = TempDir::new();
+        let systemd = backend(RecordingRunner::new(), &dir);
+
+        assert_eq!(systemd.remove_items().unwrap(), 0);
+        assert_eq!(systemd.runner.calls(), vec!["systemctl --user daemon-reload"]);
+    }
+
+    #[test]
+    fn set_normal_writes_and_enables_the_service() {
+        let dir = TempDir::new();
+        let systemd = backend(RecordingRunner::new(), &dir);
+        let command = AutostartCommand { program: "/usr/bin/easistartup".to_string(), args: Vec::new() };
+
+        systemd.set_normal(&command, true).unwrap();
+        assert_eq!(fs::read_to_string(dir.0.join(SERVICE)).unwrap(), self_unit(&command));
+        systemd.set_normal(&command, false).unwrap();
+        assert!(!dir.0.join(SERVICE).exists());
+        // 已经删除时不再调用systemctl
+        systemd.set_normal(&command, false).unwrap();
+
+        assert_eq!(
+            systemd.runner.calls(),
+            vec![
+                "systemctl --user daemon-reload",
+                "systemctl --user enable easistartup.service",
+                "systemctl --user disable easistartup.service",
+                "systemctl --user daemon-reload",
+            ]
+        );
+    }
+
+    #[test]
+    fn is_normal_enabled_uses_the_exit_code() {
+        let dir = TempDir::new();
+        let enabled = backend(RecordingRunner::new(), &dir);
+        assert!(enabled.is_normal_enabled().unwrap());
+        assert_eq!(enabled.runner.calls(), vec!["systemctl --user is-enabled easistartup.service"]);
+
+        let disabled = backend(RecordingRunner::responding(|_, _| Output { code: Some(1), ..Output::default() }), &dir);
+        assert!(!disabled.is_normal_enabled().unwrap());
+    }
+}
@@ -0,0 +1,113 @@
+// Windows：普通自启动写入注册表Run键，管理员自启动使用计划任务
+
+use super::{AutostartBackend, AutostartCommand};
+use crate::cmdline;
+use crate::error::AppError;
+use crate::i18n::Message;
+use crate::runner::CommandRunner;
+use crate::system_startup::RUN_KEY;
+use crate::task_scheduler::definition::{ExecAction, RunLevel, TaskDefinition};
+use crate::task_scheduler::{Schtasks, TaskInfo};
+use winreg::enums::*;
+use winreg::RegKey;
+
+const APP_NAME: &str = "EasiStartup";
+const TASK_NAME: &str = "EasiStartup_AdminTask";
+
+pub struct WindowsAutostart<R> {
+    tasks: Schtasks<R>,
+}
+
+impl<R: CommandRunner> WindowsAutostart<R> {
+    pub fn new(runner: R) -> WindowsAutostart<R> {
+        WindowsAutostart { tasks: Schtasks::new(runner) }
+    }
+}
+
+// 登录时以最高权限运行EasiStartup的计划任务
+fn admin_task(command: &AutostartCommand) -> TaskDefinition {
+    let action = ExecAction {
+        command: command.program.clone(),
+        arguments: Some(cmdline::join(&command.args)),
+        working_directory: None,
+    };
+    TaskDefinition {
+        description: Some("EasiStartup 自启动任务".to_string()),
+        ..TaskDefinition::logon(action, RunLevel::HighestAvailable, None)
+    }
+}
+
+impl<R: CommandRunner> AutostartBackend for WindowsAutostart<R> {
+    fn set_normal(&self, command: &AutostartCommand, enabled: bool) -> Result<(), AppError> {
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let run_key = hkcu
+            .open_subkey_with_flags(RUN_KEY, KEY_SET_VALUE | KEY_QUERY_VALUE)
+            .map_err(|e| AppError::registry(Message::OpenRunKey, e))?;
+
+        if enabled {
+            let startup_command = format!("\"{}\" {}", command.program, cmdline::join(&command.args));
+            run_key
+                .set_value(APP_NAME, &startup_command)
+                .map_err(|e| AppError::registry(Message::SetRunValue, e))?;
+        } else {
+            // 删除注册表项（忽略不存在的错误）
+            let _ = run_key.delete_value(APP_NAME);
+        }
+
+        Ok(())
+    }
+
+    fn is_normal_enabled(&self) -> Result<bool, AppError> {
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let run_key = hkcu
+            .open_subkey(RUN_KEY)
+            .map_err(|e| AppError::registry(Message::OpenRunKey, e))?;
+
+        Ok(run_key.get_value::<String, _>(APP_NAME).is_ok())
+    }
+
+    fn set_admin(&self, command: &AutostartCommand, enabled: bool) -> Result<(), AppError> {
+        if enabled {
+            self.tasks.create(TASK_NAME, &admin_task(command))
+        } else {
+            self.tasks.delete(TASK_NAME)
+        }
+    }
+
+    fn is_admin_enabled(&self) -> Result<bool, AppError> {
+        self.tasks.exists(TASK_NAME)
+    }
+
+    fn admin_task(&self) -> Result<Option<TaskInfo>, AppError> {
+        self.tasks.query(TASK_NAME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADMIN_TASK_XML: &str = include_str!("../../tests/fixtures/task_scheduler/admin_task.xml");
+
+    // 路径中包含XML需要转义的字符
+    fn command() -> AutostartCommand {
+        AutostartCommand {
+            program: r#"C:\R&D <Tools>\"Easi"\EasiStartup.exe"#.to_string(),
+            args: vec!["--auto".to_string(), "--data-dir".to_string(), r"D:\a & b".to_string()],
+        }
+    }
+
+    #[test]
+    fn admin_task_matches_golden_xml() {
+        assert_eq!(admin_task(&command()).to_xml(), ADMIN_TASK_XML);
+    }
+
+    #[test]
+    fn admin_task_reads_back() {
+        let info = TaskInfo::from_xml(&admin_task(&command()).to_xml());
+        assert_eq!(info.command, command().program);
+        assert_eq!(info.arguments, cmdline::join(&command().args));
+        assert!(info.enabled);
+        assert!(info.highest_privileges);
+    }
+}
@@ -0,0 +1,192 @@
+// Linux：普通自启动写入 ~/.config/autostart/EasiStartup.desktop，不支持管理员自启动
+
+use super::{AutostartBackend, AutostartCommand};
+use crate::error::AppError;
+use crate::i18n::{tr, Message};
+use crate::storage;
+use crate::system_startup;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "EasiStartup.desktop";
+
+pub struct XdgAutostart {
+    // 无法确定用户目录时为None
+    dir: Option<PathBuf>,
+}
+
+impl XdgAutostart {
+    pub fn new() -> XdgAutostart {
+        XdgAutostart { dir: system_startup::xdg_autostart_dir() }
+    }
+
+    fn path(&self) -> Result<PathBuf, AppError> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(FILE_NAME))
+            .ok_or_else(|| AppError::NotFound(tr(Message::AutostartDirNotFound).to_string()))
+    }
+}
+
+// 按desktop文件规范引用Exec中的参数
+fn quote_exec(arg: &str) -> String {
+    const RESERVED: &[char] = &[
+        ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(', ')', '`',
+    ];
+    // %是字段代码的前缀
+    let arg = arg.replace('%', "%%");
+    if !arg.is_empty() && !arg.contains(RESERVED) {
+        return arg;
+    }
+
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+// 生成.desktop文件的内容；Exec先按引用规则拼接，再按字符串规则转义反斜杠
+fn desktop_file(command: &AutostartCommand) -> String {
+    let exec = std::iter::once(&command.program)
+        .chain(&command.args)
+        .map(|arg| quote_exec(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('\\', "\\\\");
+
+    format!(
+        "[Desktop Entry]\nType=Application\nName=EasiStartup\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        exec
+    )
+}
+
+impl AutostartBackend for XdgAutostart {
+    fn set_normal(&self, command: &AutostartCommand, enabled: bool) -> Result<(), AppError> {
+        let path = self.path()?;
+
+        if enabled {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| AppError::io(Message::WriteAutostartEntry, e))?;
+            }
+            storage::write_atomic(&path, desktop_file(command).as_bytes())
+                .map_err(|e| AppError::io(Message::WriteAutostartEntry, e))
+        } else {
+            match fs::remove_file(&path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(AppError::io(Message::RemoveAutostartEntry, e)),
+            }
+        }
+    }
+
+    // 文件存在且没有被桌面环境的设置禁用
+    fn is_normal_enabled(&self) -> Result<bool, AppError> {
+        let content = match fs::read_to_string(self.path()?) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(AppError::io(Message::ReadAutostartEntry, e)),
+        };
+
+        let values = system_startup::desktop_entry_values(&content);
+        Ok(values.get("Hidden").is_none_or(|value| value != "true")
+            && values.get("X-GNOME-Autostart-enabled").is_none_or(|value| value != "false"))
+    }
+
+    fn set_admin(&self, _command: &AutostartCommand, enabled: bool) -> Result<(), AppError> {
+        if enabled {
+            return Err(AppError::Unsupported(tr(Message::AdminAutostartUnsupported).to_string()));
+        }
+        Ok(())
+    }
+
+    fn is_admin_enabled(&self) -> Result<bool, AppError> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESKTOP_FILE: &str = include_str!("../../tests/fixtures/xdg/EasiStartup.desktop");
+
+    // 测试结束时删除的临时目录
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> TempDir {
+            let dir = std::env::temp_dir().join(format!("easistartup-xdg-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn command() -> AutostartCommand {
+        AutostartCommand {
+            program: "/opt/Easi Startup/easistartup".to_string(),
+            args: vec!["--auto".to_string(), "--data-dir".to_string(), "/home/user/My $Data".to_string(), "100%".to_string()],
+        }
+    }
+
+    #[test]
+    fn desktop_file_matches_golden_text() {
+        assert_eq!(desktop_file(&command()), DESKTOP_FILE);
+    }
+
+    #[test]
+    fn plain_arguments_are_not_quoted() {
+        assert_eq!(quote_exec("/usr/bin/easistartup"), "/usr/bin/easistartup");
+        assert_eq!(quote_exec("--data-dir=/srv/data"), "--data-dir=/srv/data");
+        assert_eq!(quote_exec("100%"), "100%%");
+    }
+
+    #[test]
+    fn reserved_characters_are_quoted() {
+        assert_eq!(quote_exec(""), "\"\"");
+        assert_eq!(quote_exec("My Apps"), "\"My Apps\"");
+        assert_eq!(quote_exec("50% off"), "\"50%% off\"");
+        assert_eq!(quote_exec("a;b"), "\"a;b\"");
+        assert_eq!(quote_exec("it's"), "\"it's\"");
+    }
+
+    #[test]
+    fn quoted_special_characters_are_escaped() {
+        assert_eq!(quote_exec("say \"hi\""), r#""say \"hi\"""#);
+        assert_eq!(quote_exec(r"C:\dir"), r#""C:\\dir""#);
+        assert_eq!(quote_exec("$HOME"), r#""\$HOME""#);
+        assert_eq!(quote_exec("`date`"), r#""\`date\`""#);
+    }
+
+    #[test]
+    fn entry_is_written_and_removed() {
+        let dir = TempDir::new();
+        let autostart = XdgAutostart { dir: Some(dir.0.join("autostart")) };
+        assert!(!autostart.is_normal_enabled().unwrap());
+
+        autostart.set_normal(&command(), true).unwrap();
+        assert_eq!(fs::read_to_string(dir.0.join("autostart").join(FILE_NAME)).unwrap(), DESKTOP_FILE);
+        assert!(autostart.is_normal_enabled().unwrap());
+
+        // 在桌面环境的设置中关闭后视为未启用
+        let disabled = DESKTOP_FILE.replace("X-GNOME-Autostart-enabled=true", "X-GNOME-Autostart-enabled=false");
+        fs::write(dir.0.join("autostart").join(FILE_NAME), disabled).unwrap();
+        assert!(!autostart.is_normal_enabled().unwrap());
+
+        autostart.set_normal(&command(), false).unwrap();
+        assert!(!dir.0.join("autostart").join(FILE_NAME).exists());
+        // 已经删除时同样成功
+        autostart.set_normal(&command(), false).unwrap();
+    }
+}
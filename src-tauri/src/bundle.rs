@@ -0,0 +1,509 @@
+// 启动项的导入导出
+//
+// 导出文件为JSON：{"version": 1, "exportedAt": ..., "items": [...], "settings": {...}}，
+// settings可以省略。导入时按冲突策略合并到现有启动项，并按路径映射替换盘符等路径前缀。
+
+use crate::{AppSettings, Launch, StartupItem};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    pub version: u32,
+    pub exported_at: DateTime<Local>,
+    pub items: Vec<StartupItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<AppSettings>,
+}
+
+impl Bundle {
+    pub fn new(items: Vec<StartupItem>, settings: Option<AppSettings>) -> Bundle {
+        Bundle { version: BUNDLE_VERSION, exported_at: Local::now(), items, settings }
+    }
+}
+
+// 导入的启动项ID与现有启动项相同时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictMode {
+    // 保留现有启动项
+    #[default]
+    Skip,
+    // 用导入的启动项替换
+    Overwrite,
+    // 以新ID作为另一个启动项导入
+    Duplicate,
+}
+
+impl ConflictMode {
+    pub fn parse(value: &str) -> Option<ConflictMode> {
+        match value {
+            "skip" => Some(ConflictMode::Skip),
+            "overwrite" => Some(ConflictMode::Overwrite),
+            "duplicate" => Some(ConflictMode::Duplicate),
+            _ => None,
+        }
+    }
+}
+
+// 路径前缀映射，例如把 D:\ 替换为 E:\，不区分大小写；前缀只匹配完整的路径组成部分，
+// C:\App 不会替换 C:\Apps
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathRemap {
+    pub from: String,
+    pub to: String,
+}
+
+impl PathRemap {
+    // 解析 <原前缀>=<新前缀>
+    pub fn parse(value: &str) -> Option<PathRemap> {
+        let (from, to) = value.split_once('=')?;
+        if from.is_empty() {
+            return None;
+        }
+        Some(PathRemap { from: from.to_string(), to: to.to_string() })
+    }
+
+    fn apply(&self, value: &str) -> Option<String> {
+        let prefix = value.get(..self.from.len())?;
+        let rest = &value[self.from.len()..];
+        let is_separator = |c: char| c == '\\' || c == '/';
+        let at_boundary = self.from.ends_with(is_separator) || rest.is_empty() || rest.starts_with(is_separator);
+        if at_boundary && prefix.eq_ignore_ascii_case(&self.from) {
+            Some(format!("{}{}", self.to, rest))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImportOptions {
+    pub conflict: ConflictMode,
+    pub remaps: Vec<PathRemap>,
+    // 同时导入文件中的应用设置
+    pub import_settings: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub added: usize,
+    pub overwritten: usize,
+    pub duplicated: usize,
+    pub skipped: usize,
+    pub settings_imported: bool,
+    // 因依赖的启动项不存在而移除的依赖
+    pub dropped_dependencies: Vec<DroppedDependency>,
+}
+
+// 依赖的启动项既不在导入文件中也不在现有启动项中，例如只导出了部分启动项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedDependency {
+    pub item_name: String,
+    pub dependency_id: String,
+}
+
+// 按第一个匹配的映射替换路径前缀
+fn remap(value: &mut String, remaps: &[PathRemap]) {
+    if let Some(mapped) = remaps.iter().find_map(|remap| remap.apply(value)) {
+        *value = mapped;
+    }
+}
+
+// 替换可执行文件路径、工作目录、图标和以该前缀开头的参数；命令模式的命令不做替换
+//
+// 调用前参数需已迁移为参数列表。
+pub fn remap_paths(item: &mut StartupItem, remaps: &[PathRemap]) {
+    if remaps.is_empty() {
+        return;
+    }
+
+    if let Launch::Executable { path, arguments, args } = &mut item.launch {
+        remap(path, remaps);
+        if let Some(args) = args {
+            args.iter_mut().for_each(|arg| remap(arg, remaps));
+            *arguments = crate::cmdline::join(args);
+        }
+    }
+    remap(&mut item.working_directory, remaps);
+    remap(&mut item.icon, remaps);
+}
+
+// 把导入的启动项合并到现有启动项中
+//
+// 以新ID导入的启动项，导入文件中其他启动项对它的依赖也随之改为新ID；
+// 合并后仍找不到的依赖会被移除并记录在结果中，否则保存时无法通过依赖检查。
+pub fn merge(existing: &mut Vec<StartupItem>, incoming: Vec<StartupItem>, conflict: ConflictMode) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    let mut accepted = Vec::new();
+
+    for mut item in incoming {
+        let position = existing.iter().position(|other| other.id == item.id);
+        match (position, conflict) {
+            (None, _) => {
+                summary.added += 1;
+                accepted.push((None, item));
+            }
+            (Some(_), ConflictMode::Skip) => summary.skipped += 1,
+            (Some(index), ConflictMode::Overwrite) => {
+                summary.overwritten += 1;
+                accepted.push((Some(index), item));
+            }
+            (Some(_), ConflictMode::Duplicate) => {
+                let new_id = Uuid::new_v4().to_string();
+                renamed.insert(item.id.clone(), new_id.clone());
+                item.id = new_id;
+                summary.duplicated += 1;
+                accepted.push((None, item));
+            }
+        }
+    }
+
+    let mut imported = HashSet::new();
+    for (index, mut item) in accepted {
+        for dependency in &mut item.depends_on {
+            if let Some(new_id) = renamed.get(dependency) {
+                *dependency = new_id.clone();
+            }
+        }
+        imported.insert(item.id.clone());
+        match index {
+            Some(index) => existing[index] = item,
+            None => existing.push(item),
+        }
+    }
+
+    let known: HashSet<String> = existing.iter().map(|item| item.id.clone()).collect();
+    for item in existing.iter_mut().filter(|item| imported.contains(&item.id)) {
+        item.depends_on.retain(|dependency| {
+            let found = known.contains(dependency);
+            if !found {
+                summary.dropped_dependencies.push(DroppedDependency {
+                    item_name: item.name.clone(),
+                    dependency_id: dependency.clone(),
+                });
+            }
+            found
+        });
+    }
+    summary
+}
+
+// 命令行中的导入导出操作
+#[derive(Debug, Clone)]
+pub enum CliAction {
+    // --export <文件> [--items <ID,ID...>] [--include-settings]
+    Export { path: String, item_ids: Option<Vec<String>>, include_settings: bool },
+    // --import <文件> [--conflict skip|overwrite|duplicate] [--remap <原前缀>=<新前缀>]... [--import-settings]
+    Import { path: String, options: ImportOptions },
+}
+
+// 解析命令行中的导入导出参数，没有相关参数时返回None
+pub fn parse_cli(args: &[String]) -> Result<Option<CliAction>, String> {
+    let value_of = |name: &str| -> Result<Option<String>, String> {
+        match args.iter().position(|arg| arg == name) {
+            Some(index) => args
+                .get(index + 1)
+                .filter(|value| !value.starts_with("--"))
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| format!("{} requires a value", name)),
+            None => Ok(None),
+        }
+    };
+    let flag = |name: &str| args.iter().any(|arg| arg == name);
+
+    if let Some(path) = value_of("--export")? {
+        let item_ids = value_of("--items")?
+            .map(|ids| ids.split(',').map(str::trim).filter(|id| !id.is_empty()).map(String::from).collect());
+        return Ok(Some(CliAction::Export { path, item_ids, include_settings: flag("--include-settings") }));
+    }
+
+    if let Some(path) = value_of("--import")? {
+        let conflict = match value_of("--conflict")? {
+            Some(value) => ConflictMode::parse(&value).ok_or_else(|| format!("unknown conflict mode: {}", value))?,
+            None => ConflictMode::default(),
+        };
+
+        let mut remaps = Vec::new();
+        for (index, arg) in args.iter().enumerate() {
+            if arg == "--remap" {
+                let value = args.get(index + 1).ok_or("--remap requires a value")?;
+                remaps.push(PathRemap::parse(value).ok_or_else(|| format!("invalid path remap: {}", value))?);
+            }
+        }
+
+        let options = ImportOptions { conflict, remaps, import_settings: flag("--import-settings") };
+        return Ok(Some(CliAction::Import { path, options }));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, depends_on: &[&str]) -> StartupItem {
+        StartupItem {
+            id: id.to_string(),
+            name: format!("item {}", id),
+            depends_on: depends_on.iter().map(|id| id.to_string()).collect(),
+            ..StartupItem::default()
+        }
+    }
+
+    fn dependencies(items: &[StartupItem], id: &str) -> Vec<String> {
+        items.iter().find(|item| item.id == id).unwrap().depends_on.clone()
+    }
+
+    #[test]
+    fn dependencies_on_existing_items_are_kept() {
+        let mut existing = vec![item("a", &[])];
+        let summary = merge(&mut existing, vec![item("b", &["a"]), item("c", &["b"])], ConflictMode::Skip);
+
+        assert_eq!(summary.added, 2);
+        assert!(summary.dropped_dependencies.is_empty());
+        assert_eq!(dependencies(&existing, "b"), vec!["a"]);
+        assert_eq!(dependencies(&existing, "c"), vec!["b"]);
+    }
+
+    #[test]
+    fn missing_dependencies_are_dropped() {
+        let mut existing = vec![item("a", &[])];
+        let summary = merge(&mut existing, vec![item("b", &["a", "missing"])], ConflictMode::Skip);
+
+        assert_eq!(dependencies(&existing, "b"), vec!["a"]);
+        assert_eq!(summary.dropped_dependencies, vec![DroppedDependency {
+            item_name: "item b".to_string(),
+            dependency_id: "missing".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn skipped_items_keep_their_dependencies() {
+        let mut existing = vec![item("a", &["gone"])];
+        let summary = merge(&mut existing, vec![item("a", &[])], ConflictMode::Skip);
+
+        assert_eq!(summary.skipped, 1);
+        assert!(summary.dropped_dependencies.is_empty());
+        assert_eq!(dependencies(&existing, "a"), vec!["gone"]);
+    }
+
+    #[test]
+    fn duplicated_dependencies_follow_the_new_id() {
+        let mut existing = vec![item("a", &[])];
+        let summary = merge(&mut existing, vec![item("a", &[]), item("b", &["a"])], ConflictMode::Duplicate);
+
+        assert_eq!(summary.duplicated, 1);
+        let new_id = existing[1].id.clone();
+        assert_ne!(new_id, "a");
+        assert_eq!(dependencies(&existing, "b"), vec![new_id]);
+    }
+
+    #[test]
+    fn overwritten_items_replace_the_existing_item_in_place() {
+        let mut existing = vec![item("a", &[]), item("b", &[])];
+        let mut replacement = item("a", &["b", "missing"]);
+        replacement.name = "new a".to_string();
+        let summary = merge(&mut existing, vec![replacement], ConflictMode::Overwrite);
+
+        assert_eq!(summary.overwritten, 1);
+        assert_eq!(summary.added, 0);
+        assert_eq!(existing.len(), 2);
+        assert_eq!(existing[0].id, "a");
+        assert_eq!(existing[0].name, "new a");
+        assert_eq!(dependencies(&existing, "a"), vec!["b"]);
+        assert_eq!(summary.dropped_dependencies, vec![DroppedDependency {
+            item_name: "new a".to_string(),
+            dependency_id: "missing".to_string(),
+        }]);
+    }
+
+    fn remaps(pairs: &[(&str, &str)]) -> Vec<PathRemap> {
+        pairs.iter().map(|(from, to)| PathRemap { from: from.to_string(), to: to.to_string() }).collect()
+    }
+
+    fn executable(path: &str, args: &[&str]) -> StartupItem {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        StartupItem {
+            launch: Launch::Executable {
+                path: path.to_string(),
+                arguments: crate::cmdline::join(&args),
+                args: Some(args),
+            },
+            ..item("a", &[])
+        }
+    }
+
+    fn executable_paths(item: &StartupItem) -> (String, String, Vec<String>) {
+        match &item.launch {
+            Launch::Executable { path, arguments, args } => {
+                (path.clone(), arguments.clone(), args.clone().unwrap_or_default())
+            }
+            Launch::Shell { .. } => panic!("not an executable item"),
+        }
+    }
+
+    #[test]
+    fn remap_replaces_whole_path_components_only() {
+        let remap = PathRemap { from: r"C:\App".to_string(), to: r"D:\App".to_string() };
+
+        assert_eq!(remap.apply(r"C:\App"), Some(r"D:\App".to_string()));
+        assert_eq!(remap.apply(r"C:\App\x.exe"), Some(r"D:\App\x.exe".to_string()));
+        assert_eq!(remap.apply("C:\\App/x.exe"), Some("D:\\App/x.exe".to_string()));
+        assert_eq!(remap.apply(r"c:\app\x.exe"), Some(r"D:\App\x.exe".to_string()));
+        assert_eq!(remap.apply(r"C:\Apps\x.exe"), None);
+        assert_eq!(remap.apply(r"C:\Ap"), None);
+        assert_eq!(remap.apply(r"E:\App\x.exe"), None);
+    }
+
+    #[test]
+    fn remap_with_trailing_separator_matches_everything_below() {
+        let remap = PathRemap { from: r"D:\".to_string(), to: r"E:\".to_string() };
+        assert_eq!(remap.apply(r"D:\Tools\x.exe"), Some(r"E:\Tools\x.exe".to_string()));
+        assert_eq!(remap.apply(r"D:\"), Some(r"E:\".to_string()));
+        assert_eq!(remap.apply("D:"), None);
+
+        let remap = PathRemap { from: "/home/old/".to_string(), to: "/home/new/".to_string() };
+        assert_eq!(remap.apply("/home/old/bin/app"), Some("/home/new/bin/app".to_string()));
+        assert_eq!(remap.apply("/home/older/bin/app"), None);
+    }
+
+    #[test]
+    fn remap_paths_rewrites_path_arguments_working_directory_and_icon() {
+        let mut item = executable(r"D:\Tools\app.exe", &["--config", r"D:\Tools\app.ini", r"D:\Toolsbox\x"]);
+        item.working_directory = r"d:\tools".to_string();
+        item.icon = r"D:\Tools\icon.png".to_string();
+
+        remap_paths(&mut item, &remaps(&[(r"D:\Tools", r"E:\My Apps")]));
+
+        let (path, arguments, args) = executable_paths(&item);
+        assert_eq!(path, r"E:\My Apps\app.exe");
+        assert_eq!(args, vec!["--config", r"E:\My Apps\app.ini", r"D:\Toolsbox\x"]);
+        assert_eq!(arguments, crate::cmdline::join(&args));
+        assert_eq!(item.working_directory, r"E:\My Apps");
+        assert_eq!(item.icon, r"E:\My Apps\icon.png");
+    }
+
+    #[test]
+    fn remap_paths_uses_the_first_matching_remap() {
+        let mut item = executable(r"D:\Tools\app.exe", &[]);
+        remap_paths(&mut item, &remaps(&[(r"C:\", r"X:\"), (r"D:\Tools", r"E:\Tools"), (r"D:\", r"F:\")]));
+
+        assert_eq!(executable_paths(&item).0, r"E:\Tools\app.exe");
+    }
+
+    #[test]
+    fn remap_paths_leaves_shell_commands_alone() {
+        let mut item = StartupItem {
+            launch: Launch::Shell { command: r"D:\Tools\run.cmd".to_string(), shell: crate::shell::Shell::Cmd },
+            working_directory: r"D:\Tools".to_string(),
+            ..item("a", &[])
+        };
+        remap_paths(&mut item, &remaps(&[(r"D:\", r"E:\")]));
+
+        match &item.launch {
+            Launch::Shell { command, .. } => assert_eq!(command, r"D:\Tools\run.cmd"),
+            Launch::Executable { .. } => panic!("launch mode changed"),
+        }
+        assert_eq!(item.working_directory, r"E:\Tools");
+    }
+
+    #[test]
+    fn path_remaps_are_parsed_from_pairs() {
+        assert_eq!(PathRemap::parse(r"D:\=E:\"), Some(remaps(&[(r"D:\", r"E:\")]).remove(0)));
+        assert_eq!(PathRemap::parse(r"D:\old="), Some(remaps(&[(r"D:\old", "")]).remove(0)));
+        assert_eq!(PathRemap::parse(r"=E:\"), None);
+        assert_eq!(PathRemap::parse(r"D:\"), None);
+    }
+
+    fn cli(args: &[&str]) -> Result<Option<CliAction>, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_cli(&args)
+    }
+
+    #[test]
+    fn cli_export_reads_items_and_settings_flag() {
+        match cli(&["app", "--export", "out.json", "--items", " a, ,b ", "--include-settings"]) {
+            Ok(Some(CliAction::Export { path, item_ids, include_settings })) => {
+                assert_eq!(path, "out.json");
+                assert_eq!(item_ids, Some(vec!["a".to_string(), "b".to_string()]));
+                assert!(include_settings);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        match cli(&["app", "--export", "out.json"]) {
+            Ok(Some(CliAction::Export { item_ids, include_settings, .. })) => {
+                assert_eq!(item_ids, None);
+                assert!(!include_settings);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cli_import_reads_conflict_remaps_and_settings_flag() {
+        let args = [
+            "app", "--import", "in.json", "--conflict", "overwrite",
+            "--remap", r"D:\=E:\", "--remap", "/old=/new", "--import-settings",
+        ];
+        match cli(&args) {
+            Ok(Some(CliAction::Import { path, options })) => {
+                assert_eq!(path, "in.json");
+                assert_eq!(options.conflict, ConflictMode::Overwrite);
+                assert_eq!(options.remaps, remaps(&[(r"D:\", r"E:\"), ("/old", "/new")]));
+                assert!(options.import_settings);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        match cli(&["app", "--import", "in.json"]) {
+            Ok(Some(CliAction::Import { options, .. })) => {
+                assert_eq!(options.conflict, ConflictMode::Skip);
+                assert!(options.remaps.is_empty());
+                assert!(!options.import_settings);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cli_options_without_values_are_rejected() {
+        let cases: &[(&[&str], &str)] = &[
+            (&["app", "--export"], "--export requires a value"),
+            (&["app", "--export", "--include-settings"], "--export requires a value"),
+            (&["app", "--export", "out.json", "--items"], "--items requires a value"),
+            (&["app", "--import"], "--import requires a value"),
+            (&["app", "--import", "in.json", "--conflict"], "--conflict requires a value"),
+            (&["app", "--import", "in.json", "--conflict", "--import-settings"], "--conflict requires a value"),
+            (&["app", "--import", "in.json", "--remap"], "--remap requires a value"),
+        ];
+        for (args, error) in cases {
+            assert_eq!(cli(args).unwrap_err(), *error, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn cli_rejects_invalid_values() {
+        assert_eq!(cli(&["app", "--import", "in.json", "--conflict", "merge"]).unwrap_err(), "unknown conflict mode: merge");
+        assert_eq!(cli(&["app", "--import", "in.json", "--remap", "D:"]).unwrap_err(), "invalid path remap: D:");
+        assert_eq!(cli(&["app", "--import", "in.json", "--remap", "=E:"]).unwrap_err(), "invalid path remap: =E:");
+    }
+
+    #[test]
+    fn cli_without_bundle_actions_returns_none() {
+        assert!(cli(&["app"]).unwrap().is_none());
+        assert!(cli(&["app", "--auto", "--items", "a"]).unwrap().is_none());
+    }
+}
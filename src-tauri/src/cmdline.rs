@@ -0,0 +1,192 @@
+// Windows 命令行参数的解析与拼接
+//
+// 解析规则与 CommandLineToArgvW / MSVC CRT（2008 年之后的版本）一致，
+// 拼接规则是其逆过程，保证 split(&join(args)) == args。
+
+// 按 CommandLineToArgvW 的规则拆分参数字符串（不包含程序名）
+//
+// - 空格和制表符在引号外分隔参数
+// - 2n 个反斜杠后跟 `"`：输出 n 个反斜杠，`"` 切换引号状态
+// - 2n+1 个反斜杠后跟 `"`：输出 n 个反斜杠和一个字面量 `"`
+// - 不在 `"` 之前的反斜杠按原样输出
+// - 引号内连续的 `""` 输出一个字面量 `"` 并保持在引号内
+pub fn split(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // 区分“没有参数”和“空参数”（例如 `""`）
+    let mut has_arg = false;
+    let mut in_quotes = false;
+    let mut backslashes = 0usize;
+    let mut chars = command_line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                backslashes += 1;
+                has_arg = true;
+            }
+            '"' => {
+                current.extend(std::iter::repeat_n('\\', backslashes / 2));
+                let escaped = backslashes % 2 == 1;
+                backslashes = 0;
+                has_arg = true;
+
+                if escaped {
+                    current.push('"');
+                } else if in_quotes && chars.peek() == Some(&'"') {
+                    chars.next();
+                    current.push('"');
+                } else {
+                    in_quotes = !in_quotes;
+                }
+            }
+            ' ' | '\t' if !in_quotes => {
+                current.extend(std::iter::repeat_n('\\', backslashes));
+                backslashes = 0;
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            _ => {
+                current.extend(std::iter::repeat_n('\\', backslashes));
+                backslashes = 0;
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+
+    current.extend(std::iter::repeat_n('\\', backslashes));
+    if has_arg {
+        args.push(current);
+    }
+
+    args
+}
+
+// 为单个参数加上必要的引号和转义，使其能被 split 原样还原
+pub fn quote(arg: &str) -> String {
+    let needs_quotes = arg.is_empty()
+        || arg.contains([' ', '\t', '\n', '\x0b', '"']);
+    if !needs_quotes {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+
+    let mut backslashes = 0usize;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // 引号前的反斜杠需要加倍，再额外转义引号本身
+                quoted.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+
+    // 结尾的反斜杠紧挨着闭合引号，同样需要加倍
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+// 将参数列表拼接为一个命令行字符串
+pub fn join<S: AsRef<str>>(args: &[S]) -> String {
+    args.iter()
+        .map(|arg| quote(arg.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn odd_backslashes_escape_quote() {
+        assert_eq!(split(r#"a\\\"b"#), args(&[r#"a\"b"#]));
+        assert_eq!(split(r#"a\"b"#), args(&[r#"a"b"#]));
+    }
+
+    #[test]
+    fn even_backslashes_toggle_quotes() {
+        assert_eq!(split(r#"a\\"b c"#), args(&[r#"a\b c"#]));
+    }
+
+    #[test]
+    fn backslashes_not_before_quote_are_literal() {
+        assert_eq!(split(r"C:\dir\sub\ x\\"), args(&[r"C:\dir\sub\", r"x\\"]));
+    }
+
+    #[test]
+    fn doubled_quote_inside_quotes() {
+        assert_eq!(split(r#""a""b""#), args(&[r#"a"b"#]));
+        assert_eq!(split(r#""a""b" c"#), args(&[r#"a"b"#, "c"]));
+    }
+
+    #[test]
+    fn trailing_backslashes_inside_quotes() {
+        assert_eq!(split(r#""C:\Program Files\\" b"#), args(&[r"C:\Program Files\", "b"]));
+        assert_eq!(split(r#""a\\\\""#), args(&[r"a\\"]));
+    }
+
+    #[test]
+    fn empty_arguments() {
+        assert_eq!(split(""), Vec::<String>::new());
+        assert_eq!(split("   "), Vec::<String>::new());
+        assert_eq!(split(r#""""#), args(&[""]));
+        assert_eq!(split(r#"a "" b"#), args(&["a", "", "b"]));
+    }
+
+    #[test]
+    fn tabs_separate_outside_quotes() {
+        assert_eq!(split("a\tb  \t c"), args(&["a", "b", "c"]));
+        assert_eq!(split("\"a\tb\""), args(&["a\tb"]));
+    }
+
+    #[test]
+    fn unterminated_quote_runs_to_end() {
+        assert_eq!(split(r#"a "b c"#), args(&["a", "b c"]));
+    }
+
+    #[test]
+    fn quote_only_when_needed() {
+        assert_eq!(quote(r"C:\a\b"), r"C:\a\b");
+        assert_eq!(quote(""), r#""""#);
+        assert_eq!(quote("a b"), r#""a b""#);
+        assert_eq!(quote(r"a b\"), r#""a b\\""#);
+        assert_eq!(quote(r#"a\"b"#), r#""a\\\"b""#);
+    }
+
+    #[test]
+    fn join_round_trips() {
+        let cases: &[&[&str]] = &[
+            &[],
+            &[""],
+            &["", ""],
+            &["plain", "two words"],
+            &[r"C:\Program Files\App\", "--flag"],
+            &[r#"a\"b"#, r#"a""b"#, r#"""#, r"\\"],
+            &["tab\there", "new\nline", "trailing space "],
+            &[r#"\\server\share\"#, r#"x\\\"y"#, "中文 参数"],
+        ];
+        for case in cases {
+            let expected = args(case);
+            assert_eq!(split(&join(&expected)), expected, "join produced {}", join(&expected));
+        }
+    }
+}
@@ -0,0 +1,246 @@
+// 数据目录的位置
+//
+// 按以下顺序确定：
+// 1. 命令行参数 --data-dir <路径>
+// 2. 环境变量 EASISTARTUP_DATA_DIR
+// 3. 可执行文件旁有 portable.txt 时使用 <exe目录>/data（便携模式）
+// 4. 当前用户的应用数据目录
+//
+// 加入应用数据目录之前的版本总是把数据保存在 <exe目录>/data。从这些版本升级时，
+// 如果该目录中已有启动项或设置文件，会在第4步之前继续使用它，否则升级后启动项和设置
+// 会看起来全部丢失；用户可以通过创建 portable.txt 或移动数据来改变这一点。
+// 无法获取应用数据目录时同样退回到 <exe目录>/data。
+//
+// 相对路径相对于可执行文件所在目录，开机自启时的当前目录不可预期。
+
+use serde::Serialize;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const ARG: &str = "--data-dir";
+pub const ENV_VAR: &str = "EASISTARTUP_DATA_DIR";
+const PORTABLE_MARKER: &str = "portable.txt";
+
+// 数据目录由哪一项决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Source {
+    CommandLine,
+    Environment,
+    Portable,
+    // 旧版本在 <exe目录>/data 中保存的数据
+    Legacy,
+    AppData,
+    // 无法获取应用数据目录，退回到 <exe目录>/data
+    Fallback,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDir {
+    pub path: PathBuf,
+    pub source: Source,
+}
+
+// 从命令行参数中读取 --data-dir，支持 --data-dir <路径> 和 --data-dir=<路径>
+pub fn from_args(args: &[String]) -> Option<PathBuf> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == ARG {
+            return iter.next().filter(|value| !value.is_empty()).map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix(ARG).and_then(|rest| rest.strip_prefix('=')) {
+            if !value.is_empty() {
+                return Some(PathBuf::from(value));
+            }
+        }
+    }
+    None
+}
+
+// 旧版本的数据目录中是否已有数据
+fn has_legacy_data(dir: &Path) -> bool {
+    ["startup_items.json", "app_settings.json"]
+        .iter()
+        .any(|name| dir.join(name).is_file())
+}
+
+// 按顺序确定数据目录；app_data_dir为None时（无法获取用户目录）退回到 <exe目录>/data
+pub fn resolve(
+    arg: Option<PathBuf>,
+    env: Option<OsString>,
+    exe_dir: &Path,
+    app_data_dir: Option<PathBuf>,
+) -> DataDir {
+    let absolute = |path: PathBuf| if path.is_absolute() { path } else { exe_dir.join(path) };
+    let exe_data_dir = exe_dir.join("data");
+
+    if let Some(path) = arg {
+        return DataDir { path: absolute(path), source: Source::CommandLine };
+    }
+    if let Some(path) = env.filter(|value| !value.is_empty()) {
+        return DataDir { path: absolute(PathBuf::from(path)), source: Source::Environment };
+    }
+    if exe_dir.join(PORTABLE_MARKER).is_file() {
+        return DataDir { path: exe_data_dir, source: Source::Portable };
+    }
+    // 保留旧版本的数据，见文件开头的说明
+    if has_legacy_data(&exe_data_dir) {
+        return DataDir { path: exe_data_dir, source: Source::Legacy };
+    }
+    match app_data_dir {
+        Some(path) => DataDir { path, source: Source::AppData },
+        None => DataDir { path: exe_data_dir, source: Source::Fallback },
+    }
+}
+
+// 重新启动自身时需要传递下去的参数
+static FORWARDED: OnceLock<Vec<String>> = OnceLock::new();
+
+// 记录最终使用的数据目录
+pub fn remember(dir: &DataDir) {
+    let _ = FORWARDED.set(args_for(dir));
+}
+
+// 开机自启、以管理员身份重启时追加的参数，保证新进程使用同一个数据目录
+pub fn forwarded_args() -> Vec<String> {
+    FORWARDED.get().cloned().unwrap_or_default()
+}
+
+// 只有来自命令行的目录需要传递，其余的在新进程中会得到相同的结果
+fn args_for(dir: &DataDir) -> Vec<String> {
+    match dir.source {
+        Source::CommandLine => vec![ARG.to_string(), dir.path.display().to_string()],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    // 测试结束时删除的临时目录
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> TempDir {
+            let dir = std::env::temp_dir().join(format!("easistartup-data-dir-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    struct Case {
+        name: &'static str,
+        arg: Option<&'static str>,
+        env: Option<&'static str>,
+        portable: bool,
+        legacy: bool,
+        app_data: bool,
+        // 相对于exe目录的路径，"<appdata>"表示应用数据目录
+        path: &'static str,
+        source: Source,
+    }
+
+    const CASES: &[Case] = &[
+        Case { name: "command line wins", arg: Some("cli"), env: Some("env"), portable: true, legacy: true, app_data: true, path: "cli", source: Source::CommandLine },
+        Case { name: "environment beats portable", arg: None, env: Some("env"), portable: true, legacy: true, app_data: true, path: "env", source: Source::Environment },
+        Case { name: "empty environment is ignored", arg: None, env: Some(""), portable: true, legacy: false, app_data: true, path: "data", source: Source::Portable },
+        Case { name: "portable beats legacy data", arg: None, env: None, portable: true, legacy: true, app_data: true, path: "data", source: Source::Portable },
+        Case { name: "legacy data beats app data", arg: None, env: None, portable: false, legacy: true, app_data: true, path: "data", source: Source::Legacy },
+        Case { name: "app data by default", arg: None, env: None, portable: false, legacy: false, app_data: true, path: "<appdata>", source: Source::AppData },
+        Case { name: "fallback without app data", arg: None, env: None, portable: false, legacy: false, app_data: false, path: "data", source: Source::Fallback },
+    ];
+
+    #[test]
+    fn sources_are_checked_in_order() {
+        for case in CASES {
+            let exe_dir = TempDir::new();
+            if case.portable {
+                fs::write(exe_dir.0.join(PORTABLE_MARKER), "").unwrap();
+            }
+            if case.legacy {
+                fs::create_dir_all(exe_dir.0.join("data")).unwrap();
+                fs::write(exe_dir.0.join("data").join("startup_items.json"), "[]").unwrap();
+            }
+            let app_data_dir = exe_dir.0.join("appdata");
+
+            let resolved = resolve(
+                case.arg.map(PathBuf::from),
+                case.env.map(OsString::from),
+                &exe_dir.0,
+                case.app_data.then(|| app_data_dir.clone()),
+            );
+
+            let expected = match case.path {
+                "<appdata>" => app_data_dir.clone(),
+                path => exe_dir.0.join(path),
+            };
+            assert_eq!(resolved.source, case.source, "{}", case.name);
+            assert_eq!(resolved.path, expected, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn empty_legacy_directory_is_not_used() {
+        let exe_dir = TempDir::new();
+        fs::create_dir_all(exe_dir.0.join("data").join("logs")).unwrap();
+
+        let resolved = resolve(None, None, &exe_dir.0, Some(exe_dir.0.join("appdata")));
+        assert_eq!(resolved.source, Source::AppData);
+    }
+
+    #[test]
+    fn absolute_paths_are_kept() {
+        let exe_dir = TempDir::new();
+        let absolute = exe_dir.0.join("elsewhere");
+
+        let resolved = resolve(Some(absolute.clone()), None, Path::new("/unused"), None);
+        assert_eq!(resolved.path, absolute);
+        let resolved = resolve(None, Some(absolute.clone().into_os_string()), Path::new("/unused"), None);
+        assert_eq!(resolved.path, absolute);
+    }
+
+    #[test]
+    fn data_dir_argument_is_parsed() {
+        assert_eq!(from_args(&strings(&["app", "--data-dir", "d"])), Some(PathBuf::from("d")));
+        assert_eq!(from_args(&strings(&["app", "--auto", "--data-dir=a b"])), Some(PathBuf::from("a b")));
+        assert_eq!(from_args(&strings(&["app", "--data-dir"])), None);
+        assert_eq!(from_args(&strings(&["app", "--data-dir", ""])), None);
+        assert_eq!(from_args(&strings(&["app", "--data-dir="])), None);
+        assert_eq!(from_args(&strings(&["app", "--data-directory=x"])), None);
+        assert_eq!(from_args(&strings(&["app"])), None);
+    }
+
+    #[test]
+    fn only_command_line_directories_are_forwarded() {
+        let path = PathBuf::from("/srv/easi startup");
+        for source in [Source::Environment, Source::Portable, Source::Legacy, Source::AppData, Source::Fallback] {
+            assert!(args_for(&DataDir { path: path.clone(), source }).is_empty(), "{:?}", source);
+        }
+
+        let args = args_for(&DataDir { path: path.clone(), source: Source::CommandLine });
+        assert_eq!(args, strings(&["--data-dir", "/srv/easi startup"]));
+        // 新进程能从转发的参数中读回同一个目录
+        assert_eq!(from_args(&args), Some(path));
+    }
+
+    #[test]
+    fn remembered_directory_is_forwarded() {
+        let dir = DataDir { path: PathBuf::from("/srv/data"), source: Source::CommandLine };
+        remember(&dir);
+        assert_eq!(forwarded_args(), strings(&["--data-dir", "/srv/data"]));
+    }
+}
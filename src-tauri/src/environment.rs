@@ -0,0 +1,169 @@
+// 启动项环境变量的覆盖与展开
+
+use std::collections::BTreeMap;
+
+// 在覆盖表中查找变量，Windows上变量名不区分大小写
+fn find_override<'a>(
+    overrides: &'a BTreeMap<String, Option<String>>,
+    name: &str,
+) -> Option<&'a Option<String>> {
+    overrides.get(name).or_else(|| {
+        if cfg!(windows) {
+            overrides
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value)
+        } else {
+            None
+        }
+    })
+}
+
+// 获取变量值：优先使用启动项的覆盖，其次是当前进程的环境
+pub fn lookup(overrides: &BTreeMap<String, Option<String>>, name: &str) -> Option<String> {
+    match find_override(overrides, name) {
+        Some(value) => value.clone(),
+        None => std::env::var(name).ok(),
+    }
+}
+
+// 展开字符串中的 %VAR% 和 ${VAR} 引用
+//
+// 与 cmd 的行为一致，未定义的变量保持原样，不成对的 `%` 也按字面量保留。
+pub fn expand<F>(input: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find(['%', '$']) {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        let reference = if let Some(after) = rest.strip_prefix('%') {
+            after.find('%').map(|end| (&after[..end], end + 2))
+        } else if let Some(after) = rest.strip_prefix("${") {
+            after.find('}').map(|end| (&after[..end], end + 3))
+        } else {
+            None
+        };
+
+        match reference {
+            Some((name, len)) if is_valid_name(name) => {
+                match lookup(name) {
+                    Some(value) => output.push_str(&value),
+                    None => output.push_str(&rest[..len]),
+                }
+                rest = &rest[len..];
+            }
+            _ => {
+                // 不是合法的变量引用，只输出当前字符
+                output.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['%', '$', '{', '}', '=', '\n'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 不会出现在真实环境中的变量名
+    const UNDEFINED: &str = "EASISTARTUP_TEST_UNDEFINED";
+
+    fn overrides(values: &[(&str, Option<&str>)]) -> BTreeMap<String, Option<String>> {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.map(str::to_string)))
+            .collect()
+    }
+
+    // 只认识APP和EMPTY两个变量
+    fn expand_test(input: &str) -> String {
+        expand(input, |name| match name {
+            "APP" => Some(r"C:\App".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn references_are_expanded() {
+        assert_eq!(expand_test(r"%APP%\bin"), r"C:\App\bin");
+        assert_eq!(expand_test("${APP}/bin"), r"C:\App/bin");
+        assert_eq!(expand_test("%APP%%APP%"), r"C:\AppC:\App");
+        assert_eq!(expand_test("[%EMPTY%]"), "[]");
+    }
+
+    #[test]
+    fn undefined_variables_are_left_unchanged() {
+        assert_eq!(expand_test("%MISSING%/x"), "%MISSING%/x");
+        assert_eq!(expand_test("${MISSING}/x"), "${MISSING}/x");
+    }
+
+    #[test]
+    fn unpaired_markers_are_literal() {
+        assert_eq!(expand_test("50% off"), "50% off");
+        assert_eq!(expand_test("%APP"), "%APP");
+        assert_eq!(expand_test("${APP"), "${APP");
+        assert_eq!(expand_test("$APP and $"), "$APP and $");
+        assert_eq!(expand_test("100%% done"), "100%% done");
+        assert_eq!(expand_test("%%APP%"), r"%C:\App");
+    }
+
+    #[test]
+    fn invalid_names_are_not_references() {
+        assert_eq!(expand_test("%A=B%"), "%A=B%");
+        assert_eq!(expand_test("${A=B} %APP%"), r"${A=B} C:\App");
+        assert_eq!(expand_test("${}"), "${}");
+        assert!(is_valid_name("Program Files(x86)"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("A=B"));
+        assert!(!is_valid_name("A$B"));
+        assert!(!is_valid_name("A\nB"));
+    }
+
+    #[test]
+    fn expanded_values_are_not_expanded_again() {
+        let expanded = expand("%A%", |name| match name {
+            "A" => Some("%B%".to_string()),
+            "B" => Some("b".to_string()),
+            _ => None,
+        });
+        assert_eq!(expanded, "%B%");
+    }
+
+    #[test]
+    fn overrides_take_precedence_over_the_environment() {
+        let path = std::env::var("PATH").unwrap();
+        assert_eq!(lookup(&BTreeMap::new(), "PATH"), Some(path));
+
+        assert_eq!(lookup(&overrides(&[("PATH", Some("/opt/bin"))]), "PATH").as_deref(), Some("/opt/bin"));
+        assert_eq!(lookup(&overrides(&[(UNDEFINED, Some("1"))]), UNDEFINED).as_deref(), Some("1"));
+        assert_eq!(lookup(&BTreeMap::new(), UNDEFINED), None);
+    }
+
+    #[test]
+    fn unset_overrides_hide_the_environment() {
+        let unset = overrides(&[("PATH", None)]);
+        assert_eq!(lookup(&unset, "PATH"), None);
+        assert_eq!(expand("%PATH%", |name| lookup(&unset, name)), "%PATH%");
+    }
+
+    #[test]
+    fn override_names_ignore_case_only_on_windows() {
+        let values = overrides(&[("App_Home", Some(r"C:\App"))]);
+        assert_eq!(lookup(&values, "App_Home").as_deref(), Some(r"C:\App"));
+        assert_eq!(lookup(&values, "APP_HOME").is_some(), cfg!(windows));
+        assert_eq!(expand("%app_home%", |name| lookup(&values, name)) == r"C:\App", cfg!(windows));
+    }
+}
@@ -0,0 +1,118 @@
+// 命令返回给前端的错误类型
+//
+// 序列化为 { code, message }：code是稳定的机器可读代码，前端据此区分错误类型，
+// 日志中也以 [code] 开头，便于按代码检索；message是给用户看的说明，使用当前语言。
+
+use crate::i18n::{self, Message};
+use crate::scheduler::LaunchError;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum AppError {
+    // 读写文件等IO操作失败
+    Io { context: Message, source: io::Error },
+    // JSON等数据无法解析或序列化
+    Parse { context: Message, message: String },
+    // 注册表操作失败
+    Registry { context: Message, source: io::Error },
+    // 计划任务操作失败
+    TaskScheduler(String),
+    // systemd用户服务操作失败
+    Systemd(String),
+    // 需要管理员权限或提权失败
+    Elevation(String),
+    // 文件或资源不存在
+    NotFound(String),
+    // 输入数据不合法
+    Validation(String),
+    // 启动项启动失败
+    Launch(LaunchError),
+    // 当前系统不支持该操作
+    Unsupported(String),
+    // 其他内部错误
+    Internal(String),
+}
+
+impl AppError {
+    // context为失败的操作名称
+    pub fn io(context: Message, source: io::Error) -> AppError {
+        AppError::Io { context, source }
+    }
+
+    pub fn parse(context: Message, error: impl fmt::Display) -> AppError {
+        AppError::Parse { context, message: error.to_string() }
+    }
+
+    pub fn registry(context: Message, source: io::Error) -> AppError {
+        AppError::Registry { context, source }
+    }
+
+    // 稳定的错误代码，不随界面语言变化
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Io { .. } => "IO",
+            AppError::Parse { .. } => "PARSE",
+            AppError::Registry { .. } => "REGISTRY",
+            AppError::TaskScheduler(_) => "TASK_SCHEDULER",
+            AppError::Systemd(_) => "SYSTEMD",
+            AppError::Elevation(_) => "ELEVATION",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Validation(_) => "VALIDATION",
+            AppError::Launch(LaunchError::Spawn(_)) => "LAUNCH_SPAWN",
+            AppError::Launch(LaunchError::Exit { .. }) => "LAUNCH_EXIT",
+            AppError::Unsupported(_) => "UNSUPPORTED",
+            AppError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    // 给用户看的错误说明
+    pub fn message(&self) -> String {
+        match self {
+            AppError::Io { context, source } | AppError::Registry { context, source } => {
+                i18n::trf(Message::OperationFailed, &[&i18n::tr(*context), source])
+            }
+            AppError::Parse { context, message } => i18n::trf(Message::OperationFailed, &[&i18n::tr(*context), message]),
+            AppError::TaskScheduler(message)
+            | AppError::Systemd(message)
+            | AppError::Elevation(message)
+            | AppError::NotFound(message)
+            | AppError::Validation(message)
+            | AppError::Unsupported(message)
+            | AppError::Internal(message) => message.clone(),
+            AppError::Launch(error) => error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code(), self.message())
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::Io { source, .. } | AppError::Registry { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.message())?;
+        state.end()
+    }
+}
+
+impl From<LaunchError> for AppError {
+    fn from(error: LaunchError) -> Self {
+        AppError::Launch(error)
+    }
+}
@@ -0,0 +1,377 @@
+// 启动项运行历史，保存在 data/history.jsonl，每行一条记录
+
+use crate::storage;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// 每个启动项最多保留的记录数，追加记录后自动清理更早的记录
+const MAX_RECORDS_PER_ITEM: usize = 100;
+
+// 触发运行的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Trigger {
+    // 开机时通过 --auto 参数运行
+    Auto,
+    // 在编辑器中点击“全部运行”
+    Manual,
+    // 在编辑器中单独运行某个启动项
+    Test,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+    // 依赖未满足等原因没有启动
+    Skipped,
+}
+
+// 单次运行的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRecord {
+    pub item_id: String,
+    pub item_name: String,
+    // 开始启动的时间，未启动时为本次运行开始的时间
+    pub timestamp: DateTime<Local>,
+    pub trigger: Trigger,
+    // 从运行开始到开始启动等待的毫秒数，包括延迟和等待依赖
+    pub delay_ms: u64,
+    pub pid: Option<u32>,
+    // 是否成功创建了进程
+    pub spawned: bool,
+    pub exit_code: Option<i32>,
+    pub status: RunStatus,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+pub struct HistoryStore {
+    path: PathBuf,
+    // 串行化对历史文件的读写
+    lock: Mutex<()>,
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf) -> HistoryStore {
+        HistoryStore { path, lock: Mutex::new(()) }
+    }
+
+    // 追加记录，并清理超出数量上限的旧记录
+    pub fn append(&self, records: &[RunRecord]) -> io::Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let _guard = self.lock.lock().unwrap();
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        for record in records {
+            let line = serde_json::to_string(record).map_err(io::Error::other)?;
+            writeln!(file, "{}", line)?;
+        }
+        drop(file);
+
+        let all = self.read_all()?;
+        if count_per_item(&all).values().any(|count| *count > MAX_RECORDS_PER_ITEM) {
+            let kept = keep_latest(all, MAX_RECORDS_PER_ITEM);
+            self.write_all(&kept)?;
+        }
+        Ok(())
+    }
+
+    // 查询历史记录，按时间从新到旧排列；item_id为None时返回所有启动项的记录
+    pub fn query(&self, item_id: Option<&str>, limit: Option<usize>) -> io::Result<Vec<RunRecord>> {
+        let _guard = self.lock.lock().unwrap();
+        let mut records: Vec<RunRecord> = self
+            .read_all()?
+            .into_iter()
+            .filter(|record| item_id.is_none_or(|id| record.item_id == id))
+            .collect();
+
+        records.sort_by_key(|record| std::cmp::Reverse(record.timestamp));
+        if let Some(limit) = limit {
+            records.truncate(limit);
+        }
+        Ok(records)
+    }
+
+    // 每个启动项最近一次的记录
+    pub fn last_results(&self) -> io::Result<HashMap<String, RunRecord>> {
+        let _guard = self.lock.lock().unwrap();
+        let mut latest: HashMap<String, RunRecord> = HashMap::new();
+        for record in self.read_all()? {
+            match latest.get(&record.item_id) {
+                Some(existing) if existing.timestamp > record.timestamp => {}
+                _ => {
+                    latest.insert(record.item_id.clone(), record);
+                }
+            }
+        }
+        Ok(latest)
+    }
+
+    // 删除早于older_than的记录和item_exists返回false的已删除启动项的记录，
+    // 并且每个启动项最多保留keep_per_item条；返回删除的数量
+    pub fn prune(
+        &self,
+        older_than: Option<DateTime<Local>>,
+        keep_per_item: Option<usize>,
+        item_exists: impl Fn(&str) -> bool,
+    ) -> io::Result<usize> {
+        let _guard = self.lock.lock().unwrap();
+        let all = self.read_all()?;
+        let total = all.len();
+
+        let mut kept: Vec<RunRecord> = all
+            .into_iter()
+            .filter(|record| older_than.is_none_or(|cutoff| record.timestamp >= cutoff))
+            .filter(|record| item_exists(&record.item_id))
+            .collect();
+        if let Some(keep) = keep_per_item {
+            kept = keep_latest(kept, keep);
+        }
+
+        let removed = total - kept.len();
+        if removed > 0 {
+            self.write_all(&kept)?;
+        }
+        Ok(removed)
+    }
+
+    // 读取所有记录，跳过无法解析的行
+    fn read_all(&self) -> io::Result<Vec<RunRecord>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut records = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(record) => records.push(record),
+                Err(e) => log::warn!("Skipping malformed run history record: {}", e),
+            }
+        }
+        Ok(records)
+    }
+
+    // 用给定记录整体替换历史文件
+    fn write_all(&self, records: &[RunRecord]) -> io::Result<()> {
+        let mut content = String::new();
+        for record in records {
+            content.push_str(&serde_json::to_string(record).map_err(io::Error::other)?);
+            content.push('\n');
+        }
+
+        storage::write_atomic(&self.path, content.as_bytes())
+    }
+}
+
+fn count_per_item(records: &[RunRecord]) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for record in records {
+        *counts.entry(record.item_id.as_str()).or_insert(0) += 1;
+    }
+    counts
+}
+
+// 每个启动项只保留最新的keep条记录，结果按时间排序
+fn keep_latest(mut records: Vec<RunRecord>, keep: usize) -> Vec<RunRecord> {
+    records.sort_by_key(|record| record.timestamp);
+
+    let mut remaining = count_per_item(&records)
+        .into_iter()
+        .map(|(id, count)| (id.to_string(), count))
+        .collect::<HashMap<_, _>>();
+
+    records.retain(|record| {
+        let count = remaining.get_mut(&record.item_id).expect("counted above");
+        let keep_this = *count <= keep;
+        *count -= 1;
+        keep_this
+    });
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // 测试结束时删除的临时目录
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> TempDir {
+            let dir = std::env::temp_dir().join(format!("easistartup-history-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+
+        fn store(&self) -> HistoryStore {
+            HistoryStore::new(self.0.join("data").join("history.jsonl"))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn at(minute: i64) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 17, 8, 0, 0).unwrap() + chrono::Duration::minutes(minute)
+    }
+
+    // 在第minute分钟运行的记录
+    fn record(item_id: &str, minute: i64) -> RunRecord {
+        RunRecord {
+            item_id: item_id.to_string(),
+            item_name: item_id.to_string(),
+            timestamp: at(minute),
+            trigger: Trigger::Auto,
+            delay_ms: 0,
+            pid: Some(1),
+            spawned: true,
+            exit_code: None,
+            status: RunStatus::Succeeded,
+            error: None,
+            attempts: 1,
+        }
+    }
+
+    fn minutes(records: &[RunRecord]) -> Vec<i64> {
+        records.iter().map(|record| (record.timestamp - at(0)).num_minutes()).collect()
+    }
+
+    #[test]
+    fn missing_file_has_no_records() {
+        let dir = TempDir::new();
+        let store = dir.store();
+        assert!(store.query(None, None).unwrap().is_empty());
+        assert!(store.last_results().unwrap().is_empty());
+        assert_eq!(store.prune(None, Some(1), |_| true).unwrap(), 0);
+    }
+
+    #[test]
+    fn append_keeps_the_latest_records_per_item() {
+        let dir = TempDir::new();
+        let store = dir.store();
+
+        let mut records: Vec<RunRecord> = (0..105).map(|minute| record("a", minute)).collect();
+        records.push(record("b", 0));
+        store.append(&records).unwrap();
+
+        let a = store.query(Some("a"), None).unwrap();
+        assert_eq!(a.len(), MAX_RECORDS_PER_ITEM);
+        assert_eq!(minutes(&a).last(), Some(&5));
+        assert_eq!(store.query(Some("b"), None).unwrap().len(), 1);
+
+        // 再追加一条时删除最早的一条
+        store.append(&[record("a", 200)]).unwrap();
+        let a = store.query(Some("a"), None).unwrap();
+        assert_eq!(a.len(), MAX_RECORDS_PER_ITEM);
+        assert_eq!(minutes(&a)[0], 200);
+        assert_eq!(minutes(&a).last(), Some(&6));
+    }
+
+    #[test]
+    fn query_returns_newest_first_up_to_the_limit() {
+        let dir = TempDir::new();
+        let store = dir.store();
+        store.append(&[record("a", 2), record("b", 3)]).unwrap();
+        store.append(&[record("a", 1), record("a", 4)]).unwrap();
+
+        assert_eq!(minutes(&store.query(None, None).unwrap()), vec![4, 3, 2, 1]);
+        assert_eq!(minutes(&store.query(None, Some(2)).unwrap()), vec![4, 3]);
+        assert_eq!(minutes(&store.query(Some("a"), Some(2)).unwrap()), vec![4, 2]);
+        assert!(store.query(Some("c"), None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn last_results_pick_the_newest_record_per_item() {
+        let dir = TempDir::new();
+        let store = dir.store();
+        let failed = RunRecord { status: RunStatus::Failed, ..record("a", 5) };
+        store.append(&[failed, record("a", 3), record("b", 1)]).unwrap();
+
+        let latest = store.last_results().unwrap();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest["a"].status, RunStatus::Failed);
+        assert_eq!(latest["a"].timestamp, at(5));
+        assert_eq!(latest["b"].timestamp, at(1));
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let dir = TempDir::new();
+        let store = dir.store();
+        store.append(&[record("a", 1)]).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&store.path).unwrap();
+        writeln!(file, "{{\"itemId\": \"truncated\"\n").unwrap();
+        store.append(&[record("a", 2)]).unwrap();
+
+        assert_eq!(minutes(&store.query(None, None).unwrap()), vec![2, 1]);
+    }
+
+    #[test]
+    fn prune_removes_old_records() {
+        let dir = TempDir::new();
+        let store = dir.store();
+        store.append(&[record("a", 1), record("a", 2), record("b", 3)]).unwrap();
+
+        assert_eq!(store.prune(Some(at(2)), None, |_| true).unwrap(), 1);
+        assert_eq!(minutes(&store.query(None, None).unwrap()), vec![3, 2]);
+        assert_eq!(store.prune(Some(at(2)), None, |_| true).unwrap(), 0);
+    }
+
+    #[test]
+    fn prune_keeps_the_latest_records_per_item() {
+        let dir = TempDir::new();
+        let store = dir.store();
+        store.append(&[record("a", 1), record("a", 2), record("a", 3), record("b", 4)]).unwrap();
+
+        assert_eq!(store.prune(None, Some(2), |_| true).unwrap(), 1);
+        assert_eq!(minutes(&store.query(Some("a"), None).unwrap()), vec![3, 2]);
+        assert_eq!(store.query(Some("b"), None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn prune_removes_records_of_deleted_items() {
+        let dir = TempDir::new();
+        let store = dir.store();
+        store.append(&[record("a", 1), record("deleted", 2), record("deleted", 3)]).unwrap();
+
+        assert_eq!(store.prune(None, None, |id| id == "a").unwrap(), 2);
+        assert!(store.query(Some("deleted"), None).unwrap().is_empty());
+        assert!(!store.last_results().unwrap().contains_key("deleted"));
+        assert_eq!(store.query(Some("a"), None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn keep_latest_sorts_by_time() {
+        let records = vec![record("a", 3), record("b", 2), record("a", 1), record("a", 5), record("b", 4)];
+        let kept = keep_latest(records, 2);
+        assert_eq!(minutes(&kept), vec![2, 3, 4, 5]);
+        assert_eq!(kept.iter().filter(|record| record.item_id == "a").count(), 2);
+
+        assert!(keep_latest(vec![record("a", 1)], 0).is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+// 后端文本的多语言支持：托盘菜单、窗口标题以及返回给界面的错误信息
+//
+// 文本中的 {0}、{1} 等占位符按顺序替换为参数。
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+impl Language {
+    // 按语言标签匹配，例如 zh-CN、zh_Hans、en-GB；不支持的语言返回None
+    pub fn from_tag(tag: &str) -> Option<Language> {
+        let primary = tag.split(['-', '_', '.']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Some(Language::ZhCn),
+            "en" => Some(Language::EnUs),
+            _ => None,
+        }
+    }
+
+    // 系统语言，无法识别时使用中文
+    pub fn detect() -> Language {
+        sys_locale::get_locale()
+            .and_then(|tag| Language::from_tag(&tag))
+            .unwrap_or(Language::ZhCn)
+    }
+
+    // 设置中未指定语言时跟随系统
+    pub fn resolve(setting: Option<Language>) -> Language {
+        setting.unwrap_or_else(Language::detect)
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+// 当前使用的语言
+pub fn current() -> Language {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Language::EnUs,
+        _ => Language::ZhCn,
+    }
+}
+
+pub fn set_current(language: Language) {
+    let value = match language {
+        Language::ZhCn => 0,
+        Language::EnUs => 1,
+    };
+    CURRENT.store(value, Ordering::Relaxed);
+}
+
+macro_rules! messages {
+    ($($name:ident => $zh:literal, $en:literal;)*) => {
+        // 部分文本只在特定平台上使用
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[allow(dead_code)]
+        pub enum Message {
+            $($name,)*
+        }
+
+        impl Message {
+            pub fn text(self, language: Language) -> &'static str {
+                match (self, language) {
+                    $(
+                        (Message::$name, Language::ZhCn) => $zh,
+                        (Message::$name, Language::EnUs) => $en,
+                    )*
+                }
+            }
+
+            #[cfg(test)]
+            const ALL: &'static [Message] = &[$(Message::$name,)*];
+        }
+    };
+}
+
+messages! {
+    // 托盘菜单和窗口标题
+    TrayStartupEditor => "启动项编辑", "Startup Items";
+    TraySettings => "应用设置", "Settings";
+    TrayQuit => "退出", "Quit";
+    WindowStartupEditor => "启动项编辑", "Startup Items";
+    WindowSettings => "应用设置", "Settings";
+
+    // 带上下文的错误，{0}为下面的操作名称，{1}为原因
+    OperationFailed => "{0}失败: {1}", "Failed to {0}: {1}";
+    GetExePath => "获取可执行文件路径", "get the executable path";
+    CreateDataDir => "创建数据目录", "create the data directory";
+    OpenRunKey => "打开注册表Run键", "open the registry Run key";
+    SetRunValue => "设置注册表自启动", "set the registry autostart entry";
+    ReadSettings => "读取设置文件", "read the settings file";
+    ParseSettings => "解析设置文件", "parse the settings file";
+    SerializeSettings => "序列化设置", "serialize settings";
+    WriteSettings => "写入设置文件", "write the settings file";
+    ReadItems => "读取启动项文件", "read the startup items file";
+    ParseItems => "解析启动项", "parse startup items";
+    SerializeItems => "序列化启动项", "serialize startup items";
+    WriteItems => "写入启动项文件", "write the startup items file";
+    ReadHistory => "读取运行历史", "read run history";
+    PruneHistory => "清理运行历史", "prune run history";
+    CreateIconsDir => "创建图标目录", "create the icons directory";
+    ReadBundle => "读取导入文件", "read the import file";
+    ParseBundle => "解析导入文件", "parse the import file";
+    SerializeBundle => "序列化导出内容", "serialize the export";
+    WriteBundle => "写入导出文件", "write the export file";
+    DisableSystemEntry => "禁用系统自启动项", "disable the system autostart entry";
+    ReadAutostartEntry => "读取自启动文件", "read the autostart entry";
+    WriteAutostartEntry => "写入自启动文件", "write the autostart entry";
+    RemoveAutostartEntry => "删除自启动文件", "remove the autostart entry";
+    WriteUnitFile => "写入systemd单元文件", "write the systemd unit file";
+    RemoveUnitFile => "删除systemd单元文件", "remove the systemd unit file";
+
+    // 数据和设置
+    ExeDirNotFound => "无法获取可执行文件所在目录", "Cannot determine the executable directory";
+    DependsOnSelf => "启动项 '{0}' 不能依赖自身", "Startup item '{0}' cannot depend on itself";
+    DependencyMissing => "启动项 '{0}' 依赖的启动项不存在: {1}", "Startup item '{0}' depends on a missing item: {1}";
+    DependencyCycle => "启动项之间存在循环依赖: {0}", "Circular dependency between startup items: {0}";
+    OpenDataDirFailed => "打开数据目录失败: {0}", "Failed to open the data directory: {0}";
+    FileDialogFailed => "无法获取文件对话框的结果", "Failed to get the file dialog result";
+    ElevatedEnvironmentUnsupported => "启动项 '{0}' 以管理员身份运行程序时无法设置环境变量，请改用命令模式", "Startup item '{0}' cannot set environment variables when running a program as administrator, use command mode instead";
+    ExecutableNotFound => "可执行文件不存在: {0}", "Executable not found: {0}";
+    NewerBundleVersion => "导入文件版本{0}高于当前支持的版本{1}，请升级EasiStartup", "Import file version {0} is newer than the supported version {1}, please update EasiStartup";
+    ImportDroppedDependency => "启动项 '{0}' 依赖的启动项 {1} 不在导入文件或现有启动项中，已移除该依赖", "Removed the dependency of startup item '{0}' on {1}, which is neither in the import file nor among the existing startup items";
+    SystemEntryNotFound => "系统自启动项不存在: {0}", "System autostart entry not found: {0}";
+    SystemEntryCannotDisable => "自启动项 '{0}' 只运行一次，无法禁用", "Autostart entry '{0}' runs only once and cannot be disabled";
+    InvalidSchemaVersion => "无效的文件版本: {0}", "Invalid file version: {0}";
+    NewerSchemaVersion => "文件版本{0}高于当前支持的版本{1}，请升级EasiStartup", "File version {0} is newer than the supported version {1}, please update EasiStartup";
+    SchemaMigrationFailed => "从版本{0}升级到版本{1}失败: {2}", "Failed to migrate from version {0} to version {1}: {2}";
+
+    // 计划任务和管理员权限
+    SchtasksSpawnFailed => "启动schtasks命令失败: {0}", "Failed to run schtasks: {0}";
+    TaskXmlWriteFailed => "写入任务XML失败: {0}", "Failed to write the task XML: {0}";
+    TaskCreateFailed => "创建计划任务失败: {0}", "Failed to create the scheduled task: {0}";
+    TaskDeleteFailed => "删除计划任务失败: {0}", "Failed to delete the scheduled task: {0}";
+    TaskListFailed => "列出计划任务失败: {0}", "Failed to list scheduled tasks: {0}";
+    SystemctlSpawnFailed => "执行systemctl失败: {0}", "Failed to run systemctl: {0}";
+    SystemctlFailed => "systemctl {0} 失败: {1}", "systemctl {0} failed: {1}";
+    RestartAsAdminFailed => "以管理员身份重启失败", "Failed to restart as administrator";
+    RestartAsAdminUnsupported => "非Windows系统不支持管理员重启", "Restarting as administrator is only supported on Windows";
+    AdminAutostartUnsupported => "当前系统不支持管理员自启动", "Administrator autostart is not supported on this system";
+    AutostartDirNotFound => "无法确定自启动目录，请设置HOME或XDG_CONFIG_HOME", "Cannot determine the autostart directory, set HOME or XDG_CONFIG_HOME";
+    AdminRequiredForAdminStartup => "需要管理员权限来设置管理员自启动", "Administrator privileges are required to enable administrator autostart";
+
+    // 启动项运行
+    TaskAborted => "启动任务异常终止: {0}", "Launch task terminated unexpectedly: {0}";
+    ShellExecuteFailed => "ShellExecuteExW返回{0}", "ShellExecuteExW returned {0}";
+    ElevatedLaunchUnsupported => "非Windows系统不支持管理员权限启动", "Running as administrator is only supported on Windows";
+    EmptyExecutablePath => "可执行文件路径为空", "The executable path is empty";
+    EmptyCommand => "命令为空", "The command is empty";
+    ElevatedLaunchFailed => "以管理员身份启动程序失败: {0}", "Failed to start the program as administrator: {0}";
+    LaunchFailed => "启动程序失败: {0}", "Failed to start the program: {0}";
+    ElevatedCommandFailed => "以管理员身份执行命令失败: {0}", "Failed to run the command as administrator: {0}";
+    CommandFailed => "执行命令失败: {0}", "Failed to run the command: {0}";
+    RedirectOutputFailed => "重定向进程输出失败: {0}", "Failed to redirect process output: {0}";
+    OpenItemLogFailed => "打开启动项日志失败: {0}", "Failed to open the startup item log: {0}";
+    NoProcessHandle => "无法获取进程句柄，不能等待进程结束", "Cannot wait for the process to exit without a process handle";
+    ExitCode => "进程退出码为{0}", "The process exited with code {0}";
+    WaitTimeout => "等待进程结束超时（{0}秒）", "Timed out after {0} seconds waiting for the process to exit";
+    WaitFailed => "等待进程结束失败: {0}", "Failed to wait for the process to exit: {0}";
+
+    // 依赖
+    SkipCycle => "存在循环依赖", "Part of a circular dependency";
+    SkipDependency => "依赖的启动项 {0} 未满足条件: {1}", "Dependency {0} was not satisfied: {1}";
+    DependencyNotScheduled => "启动项未启用或不存在", "It is disabled or does not exist";
+    DependencyStopped => "启动项已停止调度", "The startup item is no longer scheduled";
+    DependencyFailed => "启动失败或被跳过", "It failed to start or was skipped";
+    DependencyExitFailed => "退出码非0", "It exited with a non-zero code";
+    WindowUnsupported => "当前系统不支持检测窗口", "Window detection is not supported on this system";
+    NoProcessId => "无法获取进程ID", "Cannot determine the process ID";
+    DependencyTimeout => "等待超时", "Timed out waiting";
+
+    // 命令行操作的输出
+    CliExported => "已导出{0}个启动项到 {1}", "Exported {0} startup items to {1}";
+    CliImported => "已从 {0} 导入：新增{1}个，覆盖{2}个，复制{3}个，跳过{4}个", "Imported from {0}: {1} added, {2} overwritten, {3} duplicated, {4} skipped";
+    CliSettingsImported => "已导入应用设置", "Imported the application settings";
+    CliSystemdInstalled => "已为{0}个启动项安装systemd用户服务", "Installed systemd user services for {0} startup items";
+    CliSystemdRemoved => "已删除{0}个启动项的systemd单元", "Removed the systemd units of {0} startup items";
+    CliItemFailed => "执行启动项 '{0}' ({1}) 失败（尝试{2}次）: {3}", "Startup item '{0}' ({1}) failed after {2} attempts: {3}";
+    CliAutoRunFailed => "自动执行启动项失败: {0}", "Failed to run startup items automatically: {0}";
+}
+
+// 当前语言下的文本
+pub fn tr(message: Message) -> &'static str {
+    message.text(current())
+}
+
+// 当前语言下的文本，并按顺序替换占位符
+pub fn trf(message: Message, args: &[&dyn fmt::Display]) -> String {
+    substitute(tr(message), args)
+}
+
+// 从左到右一次替换所有占位符，参数中的 {0} 等文本不会再被替换；没有对应参数的占位符保持原样
+fn substitute(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let arg = after.find('}').and_then(|end| {
+            let index = &after[..end];
+            if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            index.parse::<usize>().ok().and_then(|index| args.get(index)).map(|arg| (arg, end))
+        });
+        match arg {
+            Some((arg, end)) => {
+                let _ = write!(text, "{}", arg);
+                rest = &after[end + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    // 文本中出现的占位符序号
+    fn placeholders(text: &str) -> BTreeSet<String> {
+        text.split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}'))
+            .map(|(index, _)| index.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn placeholders_are_replaced_in_order() {
+        assert_eq!(substitute("{0}失败: {1}", &[&"读取", &42]), "读取失败: 42");
+        assert_eq!(substitute("{1} before {0}, {1} again", &[&"a", &"b"]), "b before a, b again");
+        assert_eq!(substitute("no placeholders", &[&1]), "no placeholders");
+    }
+
+    #[test]
+    fn arguments_are_not_substituted_again() {
+        assert_eq!(substitute("Failed to {0}: {1}", &[&"open {1}", &"denied"]), "Failed to open {1}: denied");
+        assert_eq!(substitute("{0}{1}", &[&"{", &"1}"]), "{1}");
+        assert_eq!(substitute("{0}", &[&"{0}"]), "{0}");
+    }
+
+    #[test]
+    fn unmatched_braces_are_kept() {
+        assert_eq!(substitute("{0} {1} {2}", &[&"a"]), "a {1} {2}");
+        assert_eq!(substitute("{name} {} {+0} {0", &[&"a"]), "{name} {} {+0} {0");
+        assert_eq!(substitute("{{0}}", &[&"a"]), "{a}");
+    }
+
+    #[test]
+    fn every_message_is_translated() {
+        for message in Message::ALL {
+            let zh = message.text(Language::ZhCn);
+            let en = message.text(Language::EnUs);
+            assert!(!zh.trim().is_empty(), "{:?} has no Chinese text", message);
+            assert!(!en.trim().is_empty(), "{:?} has no English text", message);
+            assert_eq!(placeholders(zh), placeholders(en), "{:?} has different placeholders", message);
+        }
+    }
+
+    #[test]
+    fn placeholders_are_numbered_from_zero() {
+        for message in Message::ALL {
+            let indices = placeholders(message.text(Language::EnUs));
+            let expected: BTreeSet<String> = (0..indices.len()).map(|index| index.to_string()).collect();
+            assert_eq!(indices, expected, "{:?}", message);
+        }
+    }
+
+    #[test]
+    fn language_tags_are_matched_by_primary_subtag() {
+        assert_eq!(Language::from_tag("zh_Hans_CN"), Some(Language::ZhCn));
+        assert_eq!(Language::from_tag("en-GB"), Some(Language::EnUs));
+        assert_eq!(Language::from_tag("EN.UTF-8"), Some(Language::EnUs));
+        assert_eq!(Language::from_tag("fr-FR"), None);
+    }
+}
@@ -0,0 +1,47 @@
+// 启动项的独立日志，位于 data/logs/<id>.log，记录每次运行的输出和结果
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+// 单个日志文件的大小上限，超过后轮换为 .log.old
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+
+pub struct ItemLog {
+    file: File,
+}
+
+// 启动项日志文件路径
+pub fn log_path(logs_dir: &Path, id: &str) -> PathBuf {
+    let file_name = id.replace(['\\', '/', ':', '*', '?', '"', '<', '>', '|'], "_");
+    logs_dir.join(format!("{}.log", file_name))
+}
+
+impl ItemLog {
+    // 以追加方式打开启动项日志，必要时先轮换旧日志
+    pub fn open(logs_dir: &Path, id: &str) -> io::Result<ItemLog> {
+        fs::create_dir_all(logs_dir)?;
+
+        let path = log_path(logs_dir, id);
+        if fs::metadata(&path).map(|meta| meta.len() > MAX_LOG_SIZE).unwrap_or(false) {
+            fs::rename(&path, path.with_extension("log.old"))?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(ItemLog { file })
+    }
+
+    // 写入一行带时间戳的记录，写入失败只记录到主日志
+    pub fn line(&mut self, message: &str) {
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+        if let Err(e) = writeln!(self.file, "[{}] {}", timestamp, message) {
+            log::warn!("Failed to write startup item log: {}", e);
+        }
+    }
+
+    // 用于重定向子进程stdout/stderr的句柄
+    pub fn output(&self) -> io::Result<Stdio> {
+        Ok(Stdio::from(self.file.try_clone()?))
+    }
+}
@@ -0,0 +1,133 @@
+// 创建启动项进程：普通启动、以管理员身份启动和通过Shell执行命令
+//
+// 启动项的启动逻辑（展开环境变量、选择启动方式、处理参数）只通过Launcher创建进程，
+// 测试时可以替换为只记录调用的实现。
+
+use crate::i18n::{trf, Message};
+use crate::item_log::ItemLog;
+use crate::process::ProcessHandle;
+use crate::scheduler::Launched;
+use crate::shell::Shell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// 测试中代替真实的进程创建
+#[cfg(test)]
+pub mod recording;
+
+// 普通启动和执行命令时的进程设置
+pub struct SpawnOptions<'a> {
+    pub working_dir: Option<PathBuf>,
+    // 值为None表示从继承的环境中移除该变量
+    pub environment: &'a BTreeMap<String, Option<String>>,
+    // 不为空时将子进程的stdout/stderr写入启动项日志
+    pub output: Option<&'a ItemLog>,
+}
+
+// 失败时返回原因，由调用方按启动方式补充上下文
+pub trait Launcher {
+    // 以普通权限启动程序
+    fn spawn(&self, program: &str, args: &[String], options: &SpawnOptions) -> Result<Launched, String>;
+    // 以管理员身份启动程序，parameters为拼接好的命令行；提权后的进程不继承环境变量和输出
+    fn spawn_elevated(&self, program: &str, parameters: &str, directory: Option<&Path>) -> Result<Launched, String>;
+    // 以普通权限通过Shell执行命令
+    fn run_shell(&self, shell: Shell, script: &str, options: &SpawnOptions) -> Result<Launched, String>;
+}
+
+// 创建真实进程的实现
+pub struct SystemLauncher;
+
+impl SystemLauncher {
+    fn spawn_command(mut cmd: Command, options: &SpawnOptions) -> Result<Launched, String> {
+        if let Some(dir) = &options.working_dir {
+            cmd.current_dir(dir);
+        }
+        for (name, value) in options.environment {
+            match value {
+                Some(value) => cmd.env(name, value),
+                None => cmd.env_remove(name),
+            };
+        }
+        if let Some(log) = options.output {
+            let map_err = |e: std::io::Error| trf(Message::RedirectOutputFailed, &[&e]);
+            cmd.stdout(log.output().map_err(map_err)?);
+            cmd.stderr(log.output().map_err(map_err)?);
+        }
+
+        // 在Windows上隐藏命令行窗口（如果启动的是控制台程序）
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let child = cmd.spawn().map_err(|e| e.to_string())?;
+        Ok(Launched { pid: Some(child.id()), process: Some(ProcessHandle::Child(child)), exit: None })
+    }
+}
+
+impl Launcher for SystemLauncher {
+    fn spawn(&self, program: &str, args: &[String], options: &SpawnOptions) -> Result<Launched, String> {
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        SystemLauncher::spawn_command(cmd, options)
+    }
+
+    fn spawn_elevated(&self, program: &str, parameters: &str, directory: Option<&Path>) -> Result<Launched, String> {
+        #[cfg(windows)]
+        {
+            let handle = shell_execute_runas(program, parameters, directory)
+                .map_err(|result| trf(Message::ShellExecuteFailed, &[&result]))?;
+            let process = handle.map(ProcessHandle::Raw);
+            Ok(Launched {
+                pid: process.as_ref().and_then(ProcessHandle::id),
+                process,
+                exit: None,
+            })
+        }
+
+        #[cfg(not(windows))]
+        {
+            let _ = (program, parameters, directory);
+            Err(crate::i18n::tr(Message::ElevatedLaunchUnsupported).to_string())
+        }
+    }
+
+    fn run_shell(&self, shell: Shell, script: &str, options: &SpawnOptions) -> Result<Launched, String> {
+        SystemLauncher::spawn_command(shell.command(script), options)
+    }
+}
+
+// 通过ShellExecuteExW以管理员身份启动程序，返回进程句柄；失败时返回结果码
+#[cfg(windows)]
+fn shell_execute_runas(file: &str, parameters: &str, directory: Option<&Path>) -> Result<Option<std::os::windows::io::OwnedHandle>, isize> {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::FromRawHandle;
+    use winapi::um::shellapi::{ShellExecuteExW, SHELLEXECUTEINFOW, SEE_MASK_NOCLOSEPROCESS};
+
+    let to_wide = |value: &OsStr| -> Vec<u16> { value.encode_wide().chain(std::iter::once(0)).collect() };
+    let file_wide = to_wide(OsStr::new(file));
+    let verb_wide = to_wide(OsStr::new("runas"));
+    let params_wide = to_wide(OsStr::new(parameters));
+    let dir_wide = directory.map(|dir| to_wide(dir.as_os_str()));
+
+    unsafe {
+        let mut info: SHELLEXECUTEINFOW = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+        // 保留进程句柄，以便等待进程结束
+        info.fMask = SEE_MASK_NOCLOSEPROCESS;
+        info.lpVerb = verb_wide.as_ptr();
+        info.lpFile = file_wide.as_ptr();
+        info.lpParameters = if parameters.is_empty() { std::ptr::null() } else { params_wide.as_ptr() };
+        info.lpDirectory = dir_wide.as_ref().map_or(std::ptr::null(), |dir| dir.as_ptr());
+        info.nShow = winapi::um::winuser::SW_HIDE;
+
+        if ShellExecuteExW(&mut info) == 0 {
+            return Err(info.hInstApp as isize);
+        }
+
+        Ok((!info.hProcess.is_null()).then(|| std::os::windows::io::OwnedHandle::from_raw_handle(info.hProcess as _)))
+    }
+}
@@ -0,0 +1,95 @@
+// 只记录调用、不创建进程的Launcher，用于测试启动逻辑
+
+use super::{Launcher, SpawnOptions};
+use crate::scheduler::Launched;
+use crate::shell::Shell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Call {
+    Spawn {
+        program: String,
+        args: Vec<String>,
+        working_dir: Option<PathBuf>,
+        environment: BTreeMap<String, Option<String>>,
+        // 是否要求把输出写入启动项日志
+        captured: bool,
+    },
+    Elevated {
+        program: String,
+        parameters: String,
+        directory: Option<PathBuf>,
+    },
+    Shell {
+        shell: Shell,
+        script: String,
+        working_dir: Option<PathBuf>,
+        environment: BTreeMap<String, Option<String>>,
+        captured: bool,
+    },
+}
+
+#[derive(Default)]
+pub struct RecordingLauncher {
+    calls: Mutex<Vec<Call>>,
+    // 不为空时每次调用都以此原因失败
+    failure: Option<String>,
+}
+
+impl RecordingLauncher {
+    pub fn new() -> RecordingLauncher {
+        RecordingLauncher::default()
+    }
+
+    // 每次调用都失败的实现
+    pub fn failing(reason: &str) -> RecordingLauncher {
+        RecordingLauncher { calls: Mutex::new(Vec::new()), failure: Some(reason.to_string()) }
+    }
+
+    // 按顺序返回记录的调用
+    pub fn calls(&self) -> Vec<Call> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, call: Call) -> Result<Launched, String> {
+        let mut calls = self.calls.lock().unwrap();
+        calls.push(call);
+        match &self.failure {
+            Some(reason) => Err(reason.clone()),
+            // 没有真实进程，用调用序号作为PID
+            None => Ok(Launched { pid: Some(calls.len() as u32), process: None, exit: None }),
+        }
+    }
+}
+
+impl Launcher for RecordingLauncher {
+    fn spawn(&self, program: &str, args: &[String], options: &SpawnOptions) -> Result<Launched, String> {
+        self.record(Call::Spawn {
+            program: program.to_string(),
+            args: args.to_vec(),
+            working_dir: options.working_dir.clone(),
+            environment: options.environment.clone(),
+            captured: options.output.is_some(),
+        })
+    }
+
+    fn spawn_elevated(&self, program: &str, parameters: &str, directory: Option<&Path>) -> Result<Launched, String> {
+        self.record(Call::Elevated {
+            program: program.to_string(),
+            parameters: parameters.to_string(),
+            directory: directory.map(Path::to_path_buf),
+        })
+    }
+
+    fn run_shell(&self, shell: Shell, script: &str, options: &SpawnOptions) -> Result<Launched, String> {
+        self.record(Call::Shell {
+            shell,
+            script: script.to_string(),
+            working_dir: options.working_dir.clone(),
+            environment: options.environment.clone(),
+            captured: options.output.is_some(),
+        })
+    }
+}
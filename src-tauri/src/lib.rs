@@ -1,34 +1,228 @@
-use tauri::{Manager, menu::{Menu, MenuItem}, tray::TrayIconBuilder, AppHandle};
+use tauri::{Emitter, Manager, menu::{Menu, MenuItem}, tray::TrayIconBuilder, AppHandle};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
-use std::thread;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use uuid::Uuid;
-use winreg::enums::*;
-use winreg::RegKey;
 use log::{info, warn, error, debug};
 
+mod autostart;
+mod bundle;
+mod cmdline;
+mod data_dir;
+mod environment;
+mod error;
+mod history;
+mod i18n;
+mod item_log;
+mod launcher;
+mod process;
+mod report;
+mod retry;
+// 目前只有Windows计划任务和systemd用户服务需要执行外部命令
+#[cfg(any(windows, feature = "systemd"))]
+mod runner;
+mod scheduler;
+mod schema;
+mod shell;
+mod storage;
+mod supervisor;
+mod system_startup;
+// 只有Windows使用计划任务
+#[cfg_attr(not(windows), allow(dead_code))]
+mod task_scheduler;
+
+use autostart::{AutostartBackend, AutostartCommand};
+use bundle::{Bundle, CliAction, ImportOptions, ImportSummary, BUNDLE_VERSION};
+use data_dir::DataDir;
+use error::AppError;
+use history::{HistoryStore, RunRecord, RunStatus, Trigger};
+use i18n::{tr, trf, Language, Message};
+use item_log::ItemLog;
+use launcher::{Launcher, SpawnOptions, SystemLauncher};
+use process::ExitInfo;
+use report::{ProgressEvent, StartupReport};
+use retry::RetryPolicy;
+use scheduler::{DependencyCondition, LaunchError, Launched};
+use shell::Shell;
+use storage::{Backups, ReadError};
+use supervisor::{RestartPolicy, SupervisedStatus, Supervisor};
+use system_startup::{DisableFailure, SystemEntry, SystemImportSummary};
+use task_scheduler::TaskInfo;
+
+// 缺少的字段使用Default中的值，旧版本的文件也能加载
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct StartupItem {
     pub id: String,
     pub name: String,
-    pub mode: String, // "normal" or "command"
-    pub executable_path: String,
+    // 启动方式，序列化时展开为mode字段及对应模式的配置项
+    #[serde(flatten)]
+    pub launch: Launch,
     pub display_name: String,
     pub icon: String,
-    pub arguments: String,
-    pub command: String,
+    // 工作目录，为空时继承EasiStartup自身的工作目录
+    #[serde(default)]
+    pub working_directory: String,
+    // 未指定工作目录时，使用可执行文件所在目录作为工作目录
+    #[serde(default)]
+    pub use_executable_directory: bool,
+    // 环境变量覆盖，值为null表示删除该变量
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, Option<String>>,
+    // 启动前展开路径、参数和命令中的%VAR%/${VAR}引用
+    #[serde(default)]
+    pub expand_environment: bool,
     pub run_as_admin: bool,
     pub enabled: bool,
     pub delay_enabled: bool,
     pub delay_seconds: u32,
+    // 等待启动项运行结束，记录退出码和输出，退出码非0视为失败
+    #[serde(default)]
+    pub wait_for_exit: bool,
+    // 等待结束的最长时间，0表示一直等待
+    #[serde(default)]
+    pub wait_timeout_seconds: u32,
+    // 开机自动执行时，启动失败后的重试策略
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    // 保活策略：启动后持有进程句柄，退出后按策略重新启动
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    // 依赖的启动项ID，全部满足dependency_condition后才会启动
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub dependency_condition: DependencyCondition,
+    // 前置启动项启动后，等待其满足条件的最长时间
+    #[serde(default = "default_dependency_timeout")]
+    pub dependency_timeout_seconds: u32,
+}
+
+fn default_dependency_timeout() -> u32 {
+    60
+}
+
+impl Default for StartupItem {
+    // 新建启动项的初始值，每次生成新的ID
+    fn default() -> Self {
+        StartupItem {
+            id: Uuid::new_v4().to_string(),
+            name: String::new(),
+            launch: Launch::Executable {
+                path: String::new(),
+                arguments: String::new(),
+                args: None,
+            },
+            display_name: String::new(),
+            icon: String::new(),
+            working_directory: String::new(),
+            use_executable_directory: false,
+            environment: BTreeMap::new(),
+            expand_environment: false,
+            run_as_admin: false,
+            enabled: true,
+            delay_enabled: false,
+            delay_seconds: 5,
+            wait_for_exit: false,
+            wait_timeout_seconds: 0,
+            retry: RetryPolicy::default(),
+            restart: RestartPolicy::default(),
+            depends_on: Vec::new(),
+            dependency_condition: DependencyCondition::default(),
+            dependency_timeout_seconds: default_dependency_timeout(),
+        }
+    }
 }
 
+// 启动项的启动方式
+//
+// 与旧版的扁平JSON兼容：mode为"normal"或"command"，其余字段与mode同级，
+// 其他模式的多余字段会被忽略，未知的mode在加载或保存时即被拒绝。
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(tag = "mode")]
+pub enum Launch {
+    // 直接启动可执行文件
+    #[serde(rename = "normal", rename_all = "camelCase")]
+    Executable {
+        #[serde(rename = "executablePath", default)]
+        path: String,
+        #[serde(default)]
+        arguments: String,
+        // 结构化参数列表，存在时原样传给进程，优先于arguments
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        args: Option<Vec<String>>,
+    },
+    // 通过Shell执行命令
+    #[serde(rename = "command")]
+    Shell {
+        #[serde(default)]
+        command: String,
+        #[serde(default)]
+        shell: Shell,
+    },
+}
+
+impl StartupItem {
+    // 获取实际传给进程的参数列表
+    fn argv(&self) -> Vec<String> {
+        match &self.launch {
+            Launch::Executable { args: Some(args), .. } => args.clone(),
+            Launch::Executable { arguments, .. } => cmdline::split(arguments),
+            Launch::Shell { .. } => Vec::new(),
+        }
+    }
+
+    // 获取启动前需要等待的延迟
+    fn delay(&self) -> Duration {
+        if self.delay_enabled {
+            Duration::from_secs(self.delay_seconds as u64)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    // 获取启动时使用的工作目录，None表示继承当前目录
+    fn working_dir(&self) -> Option<PathBuf> {
+        if !self.working_directory.is_empty() {
+            return Some(PathBuf::from(&self.working_directory));
+        }
+
+        if let (true, Launch::Executable { path, .. }) = (self.use_executable_directory, &self.launch) {
+            return Path::new(path)
+                .parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .map(Path::to_path_buf);
+        }
+
+        None
+    }
+
+    // 展开路径、参数、命令和工作目录中的环境变量引用
+    fn with_expanded_environment(&self) -> StartupItem {
+        let expand = |value: &str| {
+            environment::expand(value, |name| environment::lookup(&self.environment, name))
+        };
+
+        let mut item = self.clone();
+        item.launch = match &self.launch {
+            Launch::Executable { path, arguments, args } => Launch::Executable {
+                path: expand(path),
+                arguments: expand(arguments),
+                args: args.as_ref().map(|args| args.iter().map(|arg| expand(arg)).collect()),
+            },
+            Launch::Shell { command, shell } => Launch::Shell { command: expand(command), shell: *shell },
+        };
+        item.working_directory = expand(&self.working_directory);
+        item
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct AppSettings {
     pub auto_startup_enabled: bool,
     pub auto_startup_as_admin: bool,
@@ -38,235 +232,250 @@ pub struct AppSettings {
     pub hide_startup_reminder: bool,
     pub hide_admin_startup_reminder: bool,
     pub exit_after_startup: bool,
+    // 界面语言，为空时跟随系统
+    #[serde(default)]
+    pub language: Option<Language>,
 }
 
 // 获取数据目录路径
-fn get_data_dir(_app: &AppHandle) -> Result<PathBuf, String> {
-    // 获取可执行文件路径
-    let exe_path = std::env::current_exe()
-        .map_err(|e| format!("Failed to get executable path: {}", e))?;
-    
-    // 获取可执行文件所在目录
-    let exe_dir = exe_path.parent()
-        .ok_or("Failed to get executable directory")?;
-    
-    // 创建data子目录路径
-    let data_dir = exe_dir.join("data");
+fn get_data_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    // 启动时已确定数据目录的位置
+    let data_dir = app.state::<DataDir>().path.clone();
     
     // 确保data目录存在
     if !data_dir.exists() {
         fs::create_dir_all(&data_dir)
-            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+            .map_err(|e| AppError::io(Message::CreateDataDir, e))?;
     }
     
     Ok(data_dir)
 }
 
 // 获取启动项配置文件路径
-fn get_startup_items_file(app: &AppHandle) -> Result<PathBuf, String> {
+fn get_startup_items_file(app: &AppHandle) -> Result<PathBuf, AppError> {
     let data_dir = get_data_dir(app)?;
     Ok(data_dir.join("startup_items.json"))
 }
 
 // 获取应用设置配置文件路径
-fn get_app_settings_file(app: &AppHandle) -> Result<PathBuf, String> {
+fn get_app_settings_file(app: &AppHandle) -> Result<PathBuf, AppError> {
     let data_dir = get_data_dir(app)?;
     Ok(data_dir.join("app_settings.json"))
 }
 
-// 默认应用设置
-fn default_app_settings() -> AppSettings {
-    AppSettings {
-        auto_startup_enabled: false,
-        auto_startup_as_admin: false,
-        minimize_to_tray: true,
-        start_minimized: false,
-        check_updates: true,
-        hide_startup_reminder: false,
-        hide_admin_startup_reminder: false,
-        exit_after_startup: false,
-    }
+// 获取启动项日志目录路径
+fn get_item_logs_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let data_dir = get_data_dir(app)?;
+    Ok(data_dir.join("logs"))
 }
 
-// 获取当前可执行文件路径
-fn get_current_exe_path() -> Result<String, String> {
-    let exe_path = std::env::current_exe()
-        .map_err(|e| format!("获取可执行文件路径失败: {}", e))?
-        .to_string_lossy()
-        .to_string();
-    Ok(exe_path)
+// 获取配置文件的备份目录
+fn get_backups(app: &AppHandle) -> Result<Backups, AppError> {
+    let data_dir = get_data_dir(app)?;
+    Ok(Backups::new(data_dir.join("backups")))
 }
 
-// 设置普通自启动（注册表方式）
-fn set_normal_startup(enabled: bool) -> Result<(), String> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let run_key = hkcu
-        .open_subkey_with_flags("Software\\Microsoft\\Windows\\CurrentVersion\\Run", KEY_SET_VALUE | KEY_QUERY_VALUE)
-        .map_err(|e| format!("打开注册表Run键失败: {}", e))?;
-    
-    let app_name = "EasiStartup";
+// 数据文件损坏、已从备份恢复的提示
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataWarning {
+    pub file: String,
+    pub backup: String,
+    // 原文件无法读取的原因
+    pub error: String,
+}
+
+// 尚未被界面取走的提示；开机自动运行时界面还没有打开，需要先保存下来
+#[derive(Default)]
+struct DataWarnings(Mutex<Vec<DataWarning>>);
+
+// 读取数据文件，文件损坏时改用最新的有效备份并提示界面；文件不存在时返回None
+fn read_data_file<T: DeserializeOwned>(
+    app: &AppHandle,
+    path: &Path,
+    schema: &schema::Schema,
+    read_context: Message,
+    parse_context: Message,
+) -> Result<Option<T>, AppError> {
+    let read = storage::read_or_recover(path, &get_backups(app)?, |content| schema.load::<T>(content), schema::SchemaError::is_corrupt)
+        .map_err(|e| match e {
+            ReadError::Io(e) => AppError::io(read_context, e),
+            ReadError::Parse(e) => AppError::parse(parse_context, e),
+        })?;
+    let Some(read) = read else {
+        return Ok(None);
+    };
     
-    if enabled {
-        let exe_path = get_current_exe_path()?;
-        let startup_command = format!("\"{}\" --auto", exe_path);
-        run_key
-            .set_value(app_name, &startup_command)
-            .map_err(|e| format!("设置注册表自启动失败: {}", e))?;
-    } else {
-        // 删除注册表项（忽略不存在的错误）
-        let _ = run_key.delete_value(app_name);
+    if read.data.version < schema.version() {
+        info!("Migrated {} from version {} to {}", path.display(), read.data.version, schema.version());
     }
-    
-    Ok(())
+    if let Some(recovered) = read.recovered {
+        let warning = DataWarning {
+            file: path.display().to_string(),
+            backup: recovered.backup.display().to_string(),
+            error: recovered.error,
+        };
+        if let Err(e) = app.emit("data-warning", &warning) {
+            warn!("Failed to emit data warning: {}", e);
+        }
+        app.state::<DataWarnings>().0.lock().unwrap().push(warning);
+    }
+    Ok(Some(read.data.data))
 }
 
-// 检查普通自启动状态
+// 保存数据文件，覆盖前备份仍然有效的旧内容
+fn write_data_file<T: Serialize + DeserializeOwned>(
+    app: &AppHandle,
+    path: &Path,
+    schema: &schema::Schema,
+    data: &T,
+    serialize_context: Message,
+    write_context: Message,
+) -> Result<(), AppError> {
+    let content = schema.save(data)
+        .map_err(|e| AppError::parse(serialize_context, e))?;
+    storage::save(path, &content, &get_backups(app)?, |old| schema.load::<T>(old).is_ok())
+        .map_err(|e| AppError::io(write_context, e))
+}
+
+// 取走数据文件从备份恢复的提示
 #[tauri::command]
-fn check_normal_startup() -> Result<bool, String> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let run_key = hkcu
-        .open_subkey("Software\\Microsoft\\Windows\\CurrentVersion\\Run")
-        .map_err(|e| format!("打开注册表Run键失败: {}", e))?;
-    
-    let app_name = "EasiStartup";
-    match run_key.get_value::<String, _>(app_name) {
-        Ok(_) => Ok(true),
-        Err(_) => Ok(false),
-    }
+fn take_data_warnings(app: AppHandle) -> Vec<DataWarning> {
+    std::mem::take(&mut *app.state::<DataWarnings>().0.lock().unwrap())
 }
 
-// 设置管理员自启动（计划任务方式）
-fn set_admin_startup(enabled: bool) -> Result<(), String> {
-    let task_name = "EasiStartup_AdminTask";
-    
-    if enabled {
-        let exe_path = get_current_exe_path()?;
-        
-        // 创建计划任务的XML配置
-        let task_xml = format!(r#"
-<?xml version="1.0" encoding="UTF-16"?>
-<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
-  <RegistrationInfo>
-    <Description>EasiStartup 自启动任务</Description>
-  </RegistrationInfo>
-  <Triggers>
-    <LogonTrigger>
-      <Enabled>true</Enabled>
-    </LogonTrigger>
-  </Triggers>
-  <Principals>
-    <Principal id="Author">
-      <RunLevel>HighestAvailable</RunLevel>
-    </Principal>
-  </Principals>
-  <Settings>
-    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
-    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
-    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
-    <AllowHardTerminate>true</AllowHardTerminate>
-    <StartWhenAvailable>false</StartWhenAvailable>
-    <RunOnlyIfNetworkAvailable>false</RunOnlyIfNetworkAvailable>
-    <IdleSettings>
-      <StopOnIdleEnd>true</StopOnIdleEnd>
-      <RestartOnIdle>false</RestartOnIdle>
-    </IdleSettings>
-    <AllowStartOnDemand>true</AllowStartOnDemand>
-    <Enabled>true</Enabled>
-    <Hidden>false</Hidden>
-    <RunOnlyIfIdle>false</RunOnlyIfIdle>
-    <WakeToRun>false</WakeToRun>
-    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
-    <Priority>7</Priority>
-  </Settings>
-  <Actions Context="Author">
-    <Exec>
-      <Command>{}</Command>
-      <Arguments>--auto</Arguments>
-    </Exec>
-  </Actions>
-</Task>
-"#, exe_path);
-        
-        // 使用schtasks命令创建任务
-        let mut cmd = Command::new("schtasks");
-        cmd.args(["/create", "/tn", task_name, "/xml", "-", "/f"])
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped());
-        
-        // 在Windows上隐藏命令行窗口
-        #[cfg(windows)]
-        {
-            use std::os::windows::process::CommandExt;
-            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-        }
-        
-        let output = cmd.spawn()
-            .map_err(|e| format!("启动schtasks命令失败: {}", e))?;
-        
-        if let Some(mut stdin) = output.stdin.as_ref() {
-            use std::io::Write;
-            stdin.write_all(task_xml.as_bytes())
-                .map_err(|e| format!("写入任务XML失败: {}", e))?;
-        }
-        
-        let result = output.wait_with_output()
-            .map_err(|e| format!("等待schtasks命令完成失败: {}", e))?;
-        
-        if !result.status.success() {
-            let stderr = String::from_utf8_lossy(&result.stderr);
-            return Err(format!("创建计划任务失败: {}", stderr));
-        }
-    } else {
-        // 删除计划任务
-        let mut cmd = Command::new("schtasks");
-        cmd.args(["/delete", "/tn", task_name, "/f"]);
-        
-        // 在Windows上隐藏命令行窗口
-        #[cfg(windows)]
-        {
-            use std::os::windows::process::CommandExt;
-            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+// 默认应用设置
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            auto_startup_enabled: false,
+            auto_startup_as_admin: false,
+            minimize_to_tray: true,
+            start_minimized: false,
+            check_updates: true,
+            hide_startup_reminder: false,
+            hide_admin_startup_reminder: false,
+            exit_after_startup: false,
+            language: None,
         }
-        
-        let output = cmd.output()
-            .map_err(|e| format!("删除计划任务失败: {}", e))?;
-        
-        // 忽略任务不存在的错误
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if !stderr.contains("cannot find the file") && !stderr.contains("找不到") {
-                return Err(format!("删除计划任务失败: {}", stderr));
+    }
+}
+
+// 将旧版的参数字符串迁移为参数列表，并保持两者一致
+fn migrate_item_arguments(item: &mut StartupItem) {
+    if let Launch::Executable { arguments, args, .. } = &mut item.launch {
+        match args {
+            Some(args) => *arguments = cmdline::join(args),
+            None if !arguments.is_empty() => {
+                debug!("Migrating arguments of startup item '{}' to list form", item.name);
+                *args = Some(cmdline::split(arguments));
             }
+            None => {}
         }
     }
-    
+}
+
+// 检查启动选项的组合是否可以执行
+fn validate_launch_options(items: &[StartupItem]) -> Result<(), AppError> {
+    // 以管理员身份运行的程序由系统创建，无法传递环境变量；命令模式会在命令开头设置
+    if let Some(item) = items.iter().find(|item| {
+        item.run_as_admin && matches!(item.launch, Launch::Executable { .. }) && !item.environment.is_empty()
+    }) {
+        return Err(AppError::Validation(trf(Message::ElevatedEnvironmentUnsupported, &[&item.name])));
+    }
     Ok(())
 }
 
-// 检查管理员自启动状态
-#[tauri::command]
-fn check_admin_startup() -> Result<bool, String> {
-    let task_name = "EasiStartup_AdminTask";
-    
-    let mut cmd = Command::new("schtasks");
-    cmd.args(["/query", "/tn", task_name]);
+// 检查启动项依赖是否有效：依赖的启动项必须存在，且不能存在循环依赖
+fn validate_dependencies(items: &[StartupItem]) -> Result<(), AppError> {
+    for item in items {
+        for dependency in &item.depends_on {
+            if dependency == &item.id {
+                return Err(AppError::Validation(trf(Message::DependsOnSelf, &[&item.name])));
+            }
+            if !items.iter().any(|other| &other.id == dependency) {
+                return Err(AppError::Validation(trf(Message::DependencyMissing, &[&item.name, dependency])));
+            }
+        }
+    }
     
-    // 在Windows上隐藏命令行窗口
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    if let Some(ids) = scheduler::find_cycle(items) {
+        let names: Vec<&str> = items.iter()
+            .filter(|item| ids.contains(&item.id))
+            .map(|item| item.name.as_str())
+            .collect();
+        return Err(AppError::Validation(trf(Message::DependencyCycle, &[&names.join(", ")])));
     }
     
-    let output = cmd.output()
-        .map_err(|e| format!("查询计划任务失败: {}", e))?;
+    Ok(())
+}
+
+// 确定数据目录的位置
+fn resolve_data_dir(app: &AppHandle, arg: Option<PathBuf>) -> Result<DataDir, AppError> {
+    let exe_path = std::env::current_exe()
+        .map_err(|e| AppError::io(Message::GetExePath, e))?;
+    let exe_dir = exe_path.parent()
+        .ok_or_else(|| AppError::NotFound(tr(Message::ExeDirNotFound).to_string()))?;
     
-    Ok(output.status.success())
+    let app_data_dir = app.path().app_data_dir()
+        .inspect_err(|e| warn!("Failed to get app data directory: {}", e))
+        .ok();
+    Ok(data_dir::resolve(arg, std::env::var_os(data_dir::ENV_VAR), exe_dir, app_data_dir))
+}
+
+// 获取数据目录的位置及其来源，显示在设置中
+#[tauri::command]
+fn get_data_dir_info(app: AppHandle) -> DataDir {
+    app.state::<DataDir>().inner().clone()
+}
+
+// 在文件管理器中打开数据目录
+#[tauri::command]
+fn open_data_dir(app: AppHandle) -> Result<(), AppError> {
+    use tauri_plugin_opener::OpenerExt;
+    
+    let data_dir = get_data_dir(&app)?;
+    app.opener()
+        .open_path(data_dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| AppError::Internal(trf(Message::OpenDataDirFailed, &[&e])))
+}
+
+// 开机自启和以管理员身份重启时执行的命令
+fn autostart_command() -> Result<AutostartCommand, AppError> {
+    let mut args = vec!["--auto".to_string()];
+    args.extend(data_dir::forwarded_args());
+    Ok(AutostartCommand { program: get_current_exe_path()?, args })
+}
+
+// 获取当前可执行文件路径
+fn get_current_exe_path() -> Result<String, AppError> {
+    let exe_path = std::env::current_exe()
+        .map_err(|e| AppError::io(Message::GetExePath, e))?
+        .to_string_lossy()
+        .to_string();
+    Ok(exe_path)
+}
+
+// 检查普通自启动状态
+#[tauri::command]
+fn check_normal_startup() -> Result<bool, AppError> {
+    autostart::platform().is_normal_enabled()
+}
+
+// 检查管理员自启动状态
+#[tauri::command]
+fn check_admin_startup() -> Result<bool, AppError> {
+    autostart::platform().is_admin_enabled()
+}
+
+// 获取管理员自启动计划任务的状态
+#[tauri::command]
+fn get_admin_task_info() -> Result<Option<TaskInfo>, AppError> {
+    autostart::platform().admin_task()
 }
 
 // 检查是否以管理员身份运行
-fn is_running_as_admin() -> Result<bool, String> {
+fn is_running_as_admin() -> Result<bool, AppError> {
     // 使用Windows API直接检查，避免PowerShell调用
     #[cfg(windows)]
     {
@@ -310,8 +519,8 @@ fn is_running_as_admin() -> Result<bool, String> {
 }
 
 // 以管理员身份重启应用
-fn restart_as_admin() -> Result<(), String> {
-    let exe_path = get_current_exe_path()?;
+fn restart_as_admin() -> Result<(), AppError> {
+    let command = autostart_command()?;
     
     #[cfg(windows)]
     {
@@ -320,9 +529,9 @@ fn restart_as_admin() -> Result<(), String> {
         
         // 使用ShellExecuteW API直接启动，避免PowerShell
         unsafe {
-            let exe_path_wide: Vec<u16> = OsStr::new(&exe_path).encode_wide().chain(std::iter::once(0)).collect();
+            let exe_path_wide: Vec<u16> = OsStr::new(&command.program).encode_wide().chain(std::iter::once(0)).collect();
             let verb_wide: Vec<u16> = OsStr::new("runas").encode_wide().chain(std::iter::once(0)).collect();
-            let params_wide: Vec<u16> = OsStr::new("--auto").encode_wide().chain(std::iter::once(0)).collect();
+            let params_wide: Vec<u16> = OsStr::new(&cmdline::join(&command.args)).encode_wide().chain(std::iter::once(0)).collect();
             
             let result = winapi::um::shellapi::ShellExecuteW(
                 std::ptr::null_mut(),
@@ -334,141 +543,120 @@ fn restart_as_admin() -> Result<(), String> {
             );
             
             if result as isize <= 32 {
-                return Err("以管理员身份重启失败".to_string());
+                return Err(AppError::Elevation(tr(Message::RestartAsAdminFailed).to_string()));
             }
         }
+        
+        // 退出当前进程
+        std::process::exit(0);
     }
     
     #[cfg(not(windows))]
     {
-        return Err("非Windows系统不支持管理员重启".to_string());
+        let _ = command;
+        Err(AppError::Unsupported(tr(Message::RestartAsAdminUnsupported).to_string()))
     }
-    
-    // 退出当前进程
-    std::process::exit(0);
 }
 
 // 加载应用设置
 #[tauri::command]
-fn load_app_settings(app: AppHandle) -> Result<AppSettings, String> {
+fn load_app_settings(app: AppHandle) -> Result<AppSettings, AppError> {
     debug!("Loading application settings");
     let settings_file = get_app_settings_file(&app)?;
     
-    if !settings_file.exists() {
-        info!("Settings file does not exist, using default settings");
-        return Ok(default_app_settings());
-    }
-    
-    let content = std::fs::read_to_string(&settings_file)
-        .map_err(|e| {
-            error!("Failed to read settings file: {}", e);
-            format!("读取设置文件失败: {}", e)
-        })?;
+    let settings = read_data_file(&app, &settings_file, &schema::APP_SETTINGS, Message::ReadSettings, Message::ParseSettings)
+        .inspect_err(|e| error!("Failed to load settings file: {}", e))?;
     
-    let settings: AppSettings = serde_json::from_str(&content)
-        .map_err(|e| {
-            error!("Failed to parse settings file: {}", e);
-            format!("解析设置文件失败: {}", e)
-        })?;
-    
-    info!("Application settings loaded successfully");
-    Ok(settings)
+    match settings {
+        Some(settings) => {
+            info!("Application settings loaded successfully");
+            Ok(settings)
+        }
+        None => {
+            info!("Settings file does not exist, using default settings");
+            Ok(AppSettings::default())
+        }
+    }
 }
 
 // 保存应用设置
 #[tauri::command]
-fn save_app_settings(app: AppHandle, settings: AppSettings) -> Result<(), String> {
+fn save_app_settings(app: AppHandle, settings: AppSettings) -> Result<(), AppError> {
     info!("Saving application settings");
     let settings_file = get_app_settings_file(&app)?;
     
-    // 确保父目录存在
-    if let Some(parent) = settings_file.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| {
-                error!("Failed to create settings directory: {}", e);
-                format!("创建设置目录失败: {}", e)
-            })?;
-    }
-    
-    let content = serde_json::to_string_pretty(&settings)
-        .map_err(|e| {
-            error!("Failed to serialize settings: {}", e);
-            format!("序列化设置失败: {}", e)
-        })?;
-    
-    std::fs::write(&settings_file, content)
-        .map_err(|e| {
-            error!("Failed to write settings file: {}", e);
-            format!("写入设置文件失败: {}", e)
-        })?;
+    write_data_file(&app, &settings_file, &schema::APP_SETTINGS, &settings, Message::SerializeSettings, Message::WriteSettings)
+        .inspect_err(|e| error!("Failed to save settings file: {}", e))?;
     
     info!("Application settings saved successfully");
+    apply_language(&app, Language::resolve(settings.language));
     Ok(())
 }
 
 // 应用自启动设置
 #[tauri::command]
-fn apply_startup_settings(settings: AppSettings) -> Result<(), String> {
+fn apply_startup_settings(settings: AppSettings) -> Result<(), AppError> {
     info!("Applying startup settings: auto_startup_enabled={}, auto_startup_as_admin={}", 
           settings.auto_startup_enabled, settings.auto_startup_as_admin);
     
+    update_autostart(&autostart::platform(), &autostart_command()?, &settings, &is_running_as_admin)?;
+    
+    info!("Startup settings applied successfully");
+    Ok(())
+}
+
+// 按设置启用普通或管理员自启动，两者只保留一个；is_admin只在启用管理员自启动时检查
+fn update_autostart(
+    backend: &dyn AutostartBackend,
+    command: &AutostartCommand,
+    settings: &AppSettings,
+    is_admin: &dyn Fn() -> Result<bool, AppError>,
+) -> Result<(), AppError> {
     if settings.auto_startup_enabled {
         if settings.auto_startup_as_admin {
             info!("Setting up administrator startup");
             // 需要管理员权限的自启动
-            if !is_running_as_admin()? {
+            if !is_admin()? {
                 warn!("Administrator privileges required for admin startup");
-                return Err("需要管理员权限来设置管理员自启动".to_string());
+                return Err(AppError::Elevation(tr(Message::AdminRequiredForAdminStartup).to_string()));
             }
             
             // 删除普通自启动
             info!("Removing normal startup");
-            set_normal_startup(false)?;
+            backend.set_normal(command, false)?;
             // 设置管理员自启动
             info!("Setting admin startup");
-            set_admin_startup(true)?;
+            backend.set_admin(command, true)?;
         } else {
             info!("Setting up normal startup");
             // 普通自启动
-            set_admin_startup(false)?;
-            set_normal_startup(true)?;
+            backend.set_admin(command, false)?;
+            backend.set_normal(command, true)?;
         }
     } else {
         info!("Disabling all startup options");
         // 禁用所有自启动
-        set_normal_startup(false)?;
-        set_admin_startup(false)?;
+        backend.set_normal(command, false)?;
+        backend.set_admin(command, false)?;
     }
-    
-    info!("Startup settings applied successfully");
     Ok(())
 }
 
 // 检查当前权限状态
 #[tauri::command]
-fn check_admin_permission() -> Result<bool, String> {
+fn check_admin_permission() -> Result<bool, AppError> {
     is_running_as_admin()
 }
 
 // 请求管理员权限重启
 #[tauri::command]
-fn request_admin_restart() -> Result<(), String> {
+fn request_admin_restart() -> Result<(), AppError> {
     restart_as_admin()
 }
 
 // 检查启动项中是否有需要管理员权限的项目
-fn has_admin_startup_items(app: &AppHandle) -> Result<bool, String> {
-    let file_path = get_startup_items_file(app)?;
-    
-    if !file_path.exists() {
-        return Ok(false);
-    }
-    
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("读取启动项文件失败: {}", e))?;
-    
-    let items: Vec<StartupItem> = serde_json::from_str(&content)
-        .map_err(|e| format!("解析启动项失败: {}", e))?;
+fn has_admin_startup_items(app: &AppHandle) -> Result<bool, AppError> {
+    let items = read_startup_items(app)?;
     
     // 检查是否有启用的管理员启动项
     Ok(items.iter().any(|item| item.enabled && item.run_as_admin))
@@ -476,7 +664,7 @@ fn has_admin_startup_items(app: &AppHandle) -> Result<bool, String> {
 
 // 检查是否需要显示自启动提醒
 #[tauri::command]
-fn check_startup_reminders(app: AppHandle) -> Result<(bool, bool), String> {
+fn check_startup_reminders(app: AppHandle) -> Result<(bool, bool), AppError> {
     let settings = load_app_settings(app.clone())?;
     
     // 如果用户已经选择不再显示提醒，直接返回false
@@ -501,7 +689,7 @@ fn check_startup_reminders(app: AppHandle) -> Result<(bool, bool), String> {
 
 // 更新提醒设置
 #[tauri::command]
-fn update_reminder_settings(app: AppHandle, hide_startup: bool, hide_admin: bool) -> Result<(), String> {
+fn update_reminder_settings(app: AppHandle, hide_startup: bool, hide_admin: bool) -> Result<(), AppError> {
     let mut settings = load_app_settings(app.clone())?;
     settings.hide_startup_reminder = hide_startup;
     settings.hide_admin_startup_reminder = hide_admin;
@@ -511,12 +699,12 @@ fn update_reminder_settings(app: AppHandle, hide_startup: bool, hide_admin: bool
 
 // 获取应用版本
 #[tauri::command]
-fn get_app_version() -> Result<String, String> {
+fn get_app_version() -> Result<String, AppError> {
     Ok(env!("CARGO_PKG_VERSION").to_string())
 }
 
 #[tauri::command]
-async fn open_file_dialog(app: AppHandle) -> Result<Option<String>, String> {
+async fn open_file_dialog(app: AppHandle) -> Result<Option<String>, AppError> {
     use tauri_plugin_dialog::DialogExt;
     use std::sync::mpsc;
     
@@ -534,56 +722,171 @@ async fn open_file_dialog(app: AppHandle) -> Result<Option<String>, String> {
     match rx.recv() {
         Ok(Some(path)) => Ok(Some(path.to_string())),
         Ok(None) => Ok(None),
-        Err(_) => Err("Failed to receive file dialog result".to_string()),
+        Err(_) => Err(AppError::Internal(tr(Message::FileDialogFailed).to_string())),
     }
 }
 
 #[tauri::command]
-async fn load_startup_items(app: AppHandle) -> Result<Vec<StartupItem>, String> {
-    let file_path = get_startup_items_file(&app)?;
+async fn load_startup_items(app: AppHandle) -> Result<Vec<StartupItem>, AppError> {
+    read_startup_items(&app)
+}
+
+// 读取启动项文件，旧版本的文件升级为当前格式
+fn read_startup_items(app: &AppHandle) -> Result<Vec<StartupItem>, AppError> {
+    let file_path = get_startup_items_file(app)?;
     
-    if !file_path.exists() {
-        return Ok(vec![]);
-    }
+    let mut items: Vec<StartupItem> = read_data_file(app, &file_path, &schema::STARTUP_ITEMS, Message::ReadItems, Message::ParseItems)?
+        .unwrap_or_default();
+    items.iter_mut().for_each(migrate_item_arguments);
+    
+    Ok(items)
+}
+
+#[tauri::command]
+async fn save_startup_items(app: AppHandle, items: Vec<StartupItem>) -> Result<(), AppError> {
+    write_startup_items(&app, items)
+}
+
+// 检查并保存启动项
+fn write_startup_items(app: &AppHandle, mut items: Vec<StartupItem>) -> Result<(), AppError> {
+    let file_path = get_startup_items_file(app)?;
     
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read startup items file: {}", e))?;
+    items.iter_mut().for_each(migrate_item_arguments);
+    validate_launch_options(&items)?;
+    validate_dependencies(&items)?;
     
-    let items: Vec<StartupItem> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse startup items: {}", e))?;
+    write_data_file(app, &file_path, &schema::STARTUP_ITEMS, &items, Message::SerializeItems, Message::WriteItems)
+}
+
+// 选择导入或导出的文件，save为true时选择保存位置
+#[tauri::command]
+async fn select_bundle_file(app: AppHandle, save: bool) -> Result<Option<String>, AppError> {
+    use tauri_plugin_dialog::DialogExt;
+    use std::sync::mpsc;
     
-    Ok(items)
+    let (tx, rx) = mpsc::channel();
+    let dialog = app.dialog()
+        .file()
+        .add_filter("EasiStartup", &["json"])
+        .add_filter("All files", &["*"]);
+    if save {
+        dialog
+            .set_file_name("easistartup-items.json")
+            .save_file(move |file_path| {
+                let _ = tx.send(file_path);
+            });
+    } else {
+        dialog.pick_file(move |file_path| {
+            let _ = tx.send(file_path);
+        });
+    }
+    
+    match rx.recv() {
+        Ok(Some(path)) => Ok(Some(path.to_string())),
+        Ok(None) => Ok(None),
+        Err(_) => Err(AppError::Internal(tr(Message::FileDialogFailed).to_string())),
+    }
 }
 
+// 导出启动项，item_ids为空时导出全部；返回导出的启动项数量
 #[tauri::command]
-async fn save_startup_items(app: AppHandle, items: Vec<StartupItem>) -> Result<(), String> {
-    let file_path = get_startup_items_file(&app)?;
+async fn export_startup_items(app: AppHandle, path: String, item_ids: Option<Vec<String>>, include_settings: bool) -> Result<usize, AppError> {
+    export_bundle(&app, Path::new(&path), item_ids.as_deref(), include_settings)
+}
+
+fn export_bundle(app: &AppHandle, path: &Path, item_ids: Option<&[String]>, include_settings: bool) -> Result<usize, AppError> {
+    let items: Vec<StartupItem> = read_startup_items(app)?
+        .into_iter()
+        .filter(|item| item_ids.is_none_or(|ids| ids.contains(&item.id)))
+        .collect();
+    let settings = if include_settings { Some(load_app_settings(app.clone())?) } else { None };
+    let count = items.len();
+    
+    let content = serde_json::to_string_pretty(&Bundle::new(items, settings))
+        .map_err(|e| AppError::parse(Message::SerializeBundle, e))?;
+    storage::write_atomic(path, content.as_bytes())
+        .map_err(|e| AppError::io(Message::WriteBundle, e))?;
+    
+    info!("Exported {} startup items to {}", count, path.display());
+    Ok(count)
+}
+
+// 从文件导入启动项
+#[tauri::command]
+async fn import_startup_items(app: AppHandle, path: String, options: ImportOptions) -> Result<ImportSummary, AppError> {
+    import_bundle(&app, Path::new(&path), &options)
+}
+
+fn import_bundle(app: &AppHandle, path: &Path, options: &ImportOptions) -> Result<ImportSummary, AppError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| AppError::io(Message::ReadBundle, e))?;
+    let bundle: Bundle = serde_json::from_str(&content)
+        .map_err(|e| AppError::parse(Message::ParseBundle, e))?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(AppError::Validation(trf(Message::NewerBundleVersion, &[&bundle.version, &BUNDLE_VERSION])));
+    }
     
-    let content = serde_json::to_string_pretty(&items)
-        .map_err(|e| format!("Failed to serialize startup items: {}", e))?;
+    let mut incoming = bundle.items;
+    for item in &mut incoming {
+        migrate_item_arguments(item);
+        bundle::remap_paths(item, &options.remaps);
+    }
     
-    fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write startup items file: {}", e))?;
+    let mut items = read_startup_items(app)?;
+    let mut summary = bundle::merge(&mut items, incoming, options.conflict);
+    write_startup_items(app, items)?;
     
-    Ok(())
+    if let (true, Some(settings)) = (options.import_settings, bundle.settings) {
+        save_app_settings(app.clone(), settings.clone())?;
+        // 自启动设置需要写入注册表或计划任务才能生效，失败时不影响已导入的内容
+        if let Err(e) = apply_startup_settings(settings) {
+            warn!("Failed to apply imported startup settings: {}", e);
+        }
+        summary.settings_imported = true;
+    }
+    
+    for dropped in &summary.dropped_dependencies {
+        warn!("Dropped dependency of imported startup item '{}' on missing item {}", dropped.item_name, dropped.dependency_id);
+    }
+    info!("Imported startup items from {}: {:?}", path.display(), summary);
+    Ok(summary)
+}
+
+// 执行命令行中的导入导出操作，返回进程退出码
+fn run_cli_action(app: &AppHandle, action: CliAction) -> i32 {
+    let result = match action {
+        CliAction::Export { path, item_ids, include_settings } => {
+            export_bundle(app, Path::new(&path), item_ids.as_deref(), include_settings)
+                .map(|count| println!("{}", trf(Message::CliExported, &[&count, &path])))
+        }
+        CliAction::Import { path, options } => {
+            import_bundle(app, Path::new(&path), &options).map(|summary| {
+                println!("{}", trf(Message::CliImported, &[
+                    &path, &summary.added, &summary.overwritten, &summary.duplicated, &summary.skipped,
+                ]));
+                if summary.settings_imported {
+                    println!("{}", tr(Message::CliSettingsImported));
+                }
+                for dropped in &summary.dropped_dependencies {
+                    eprintln!("{}", trf(Message::ImportDroppedDependency, &[&dropped.item_name, &dropped.dependency_id]));
+                }
+            })
+        }
+    };
+    
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error!("Command line import/export failed: {}", e);
+            eprintln!("{}", e);
+            1
+        }
+    }
 }
 
 #[tauri::command]
 fn create_startup_item() -> StartupItem {
-    StartupItem {
-        id: Uuid::new_v4().to_string(),
-        name: String::new(),
-        mode: "normal".to_string(),
-        executable_path: String::new(),
-        display_name: String::new(),
-        icon: String::new(),
-        arguments: String::new(),
-        command: String::new(),
-        run_as_admin: false,
-        enabled: true,
-        delay_enabled: false,
-        delay_seconds: 5,
-    }
+    StartupItem::default()
 }
 
 #[tauri::command]
@@ -602,8 +905,115 @@ fn show_settings(app: tauri::AppHandle) {
     }
 }
 
+// 按当前语言创建托盘菜单
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let startup_editor = MenuItem::with_id(app, "startup_editor", tr(Message::TrayStartupEditor), true, None::<&str>)?;
+    let settings = MenuItem::with_id(app, "settings", tr(Message::TraySettings), true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", tr(Message::TrayQuit), true, None::<&str>)?;
+    Menu::with_items(app, &[&startup_editor, &settings, &quit])
+}
+
+// 按当前语言设置窗口标题
+fn update_window_titles(app: &AppHandle) {
+    for (label, title) in [("startup-editor", Message::WindowStartupEditor), ("settings", Message::WindowSettings)] {
+        if let Some(window) = app.get_webview_window(label) {
+            if let Err(e) = window.set_title(tr(title)) {
+                warn!("Failed to set title of window '{}': {}", label, e);
+            }
+        }
+    }
+}
+
+// 切换界面语言，更新托盘菜单和窗口标题
+fn apply_language(app: &AppHandle, language: Language) {
+    if language == i18n::current() {
+        return;
+    }
+    info!("Switching language to {:?}", language);
+    i18n::set_current(language);
+    
+    if let Some(tray) = app.tray_by_id("main") {
+        match build_tray_menu(app) {
+            Ok(menu) => {
+                if let Err(e) = tray.set_menu(Some(menu)) {
+                    warn!("Failed to update tray menu: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to build tray menu: {}", e),
+        }
+    }
+    update_window_titles(app);
+}
+
+// 为启动项安装或删除systemd用户服务，返回进程退出码
+#[cfg(all(not(windows), feature = "systemd"))]
+fn run_systemd_items_action(app: &AppHandle, action: autostart::systemd::ItemsAction) -> i32 {
+    use autostart::systemd::ItemsAction;
+    
+    let backend = autostart::platform();
+    let result = match action {
+        ItemsAction::Install => read_startup_items(app)
+            .and_then(|items| backend.install_items(&items))
+            .map(|count| println!("{}", trf(Message::CliSystemdInstalled, &[&count]))),
+        ItemsAction::Remove => backend
+            .remove_items()
+            .map(|count| println!("{}", trf(Message::CliSystemdRemoved, &[&count]))),
+    };
+    
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error!("Failed to update systemd units for startup items: {}", e);
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+// 列出系统中已有的自启动项
 #[tauri::command]
-async fn get_shortcut_info(executable_path: String) -> Result<Option<(String, String)>, String> {
+fn list_system_startup_entries() -> Result<Vec<SystemEntry>, AppError> {
+    let exe_path = std::env::current_exe()
+        .map_err(|e| AppError::io(Message::GetExePath, e))?;
+    Ok(system_startup::list(&exe_path))
+}
+
+// 把系统自启动项导入为启动项，disable_original为true时随后禁用原来的自启动项
+#[tauri::command]
+fn import_system_startup_entries(app: AppHandle, ids: Vec<String>, disable_original: bool) -> Result<SystemImportSummary, AppError> {
+    let entries = list_system_startup_entries()?;
+    let selected = ids.iter()
+        .map(|id| {
+            entries.iter()
+                .find(|entry| &entry.id == id)
+                .ok_or_else(|| AppError::NotFound(trf(Message::SystemEntryNotFound, &[id])))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut items = read_startup_items(&app)?;
+    items.extend(selected.iter().map(|entry| entry.to_startup_item()));
+    write_startup_items(&app, items)?;
+    
+    // 保存成功后再禁用，避免两边都不再自启动
+    let mut summary = SystemImportSummary { imported: selected.len(), ..Default::default() };
+    if disable_original {
+        for entry in selected.iter().filter(|entry| entry.enabled && entry.can_disable) {
+            match system_startup::disable(entry) {
+                Ok(()) => summary.disabled += 1,
+                Err(e) => {
+                    warn!("Failed to disable system autostart entry {}: {}", entry.id, e);
+                    summary.failures.push(DisableFailure { name: entry.name.clone(), error: e.message() });
+                }
+            }
+        }
+    }
+    
+    info!("Imported {} system autostart entries, disabled {}", summary.imported, summary.disabled);
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn get_shortcut_info(executable_path: String) -> Result<Option<(String, String)>, AppError> {
     // 检查是否是快捷方式文件
     if !executable_path.to_lowercase().ends_with(".lnk") {
         return Ok(None);
@@ -625,213 +1035,446 @@ async fn get_shortcut_info(executable_path: String) -> Result<Option<(String, St
         }
     }
     
-    #[cfg(not(windows))]
-    {
-        return Ok(None);
-    }
-    
     Ok(None)
 }
 
 // 执行单个启动项
 #[tauri::command]
-async fn execute_startup_item(item: StartupItem) -> Result<(), String> {
+async fn execute_startup_item(app: AppHandle, item: StartupItem) -> Result<(), AppError> {
     info!("Executing startup item: {} (ID: {})", item.name, item.id);
     
+    let logs_dir = get_item_logs_dir(&app)?;
+    let started_at = chrono::Local::now();
+    let delay = item.delay();
+    let record_app = app.clone();
+    let record_item = item.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        run_single_startup_item(&SystemLauncher, &std::thread::sleep, &item, &logs_dir).map(|result| {
+            result
+                .map(|launched| supervise_if_needed(&app, &item, launched))
+                .inspect_err(|e| supervise_failed_exit(&app, &item, e))
+        })
+    })
+    .await
+    .map_err(|e| AppError::Internal(trf(Message::TaskAborted, &[&e])))?;
+    let Some(result) = result else {
+        return Ok(());
+    };
+    
+    let (pid, exit_code, error) = match &result {
+        Ok(launched) => (launched.pid, launched.exit.and_then(|exit| exit.code), None),
+        Err(e) => {
+            let code = match e {
+                LaunchError::Exit { code, .. } => *code,
+                LaunchError::Spawn(_) => None,
+            };
+            (None, code, Some(e.to_string()))
+        }
+    };
+    record_history(&record_app, vec![RunRecord {
+        item_id: record_item.id.clone(),
+        item_name: record_item.name.clone(),
+        timestamp: started_at + chrono::Duration::from_std(delay).unwrap_or_default(),
+        trigger: Trigger::Test,
+        delay_ms: delay.as_millis() as u64,
+        pid,
+        spawned: !matches!(result, Err(LaunchError::Spawn(_))),
+        exit_code,
+        status: if result.is_ok() { RunStatus::Succeeded } else { RunStatus::Failed },
+        error,
+        attempts: 1,
+    }]);
+    
+    result.map(|_| ()).map_err(AppError::from)
+}
+
+// 写入运行历史，失败只记录日志
+fn record_history(app: &AppHandle, records: Vec<RunRecord>) {
+    if let Err(e) = app.state::<HistoryStore>().append(&records) {
+        error!("Failed to write run history: {}", e);
+    }
+}
+
+// 将调度结果转换为运行历史记录
+fn history_record(outcome: &scheduler::Outcome, run_started_at: chrono::DateTime<chrono::Local>, trigger: Trigger) -> RunRecord {
+    let (status, error) = match &outcome.status {
+        scheduler::Status::Succeeded => (RunStatus::Succeeded, None),
+        scheduler::Status::Failed(e) => (RunStatus::Failed, Some(e.to_string())),
+        scheduler::Status::Skipped(reason) => (RunStatus::Skipped, Some(reason.to_string())),
+    };
+    let delay = outcome.started.unwrap_or_default();
+    
+    RunRecord {
+        item_id: outcome.id.clone(),
+        item_name: outcome.name.clone(),
+        timestamp: run_started_at + chrono::Duration::from_std(delay).unwrap_or_default(),
+        trigger,
+        delay_ms: delay.as_millis() as u64,
+        pid: outcome.pid,
+        spawned: outcome.spawned,
+        exit_code: outcome.exit_code,
+        status,
+        error,
+        attempts: outcome.attempts,
+    }
+}
+
+// 查询运行历史，item_id为空时返回所有启动项的记录
+#[tauri::command]
+fn get_run_history(app: AppHandle, item_id: Option<String>, limit: Option<usize>) -> Result<Vec<RunRecord>, AppError> {
+    app.state::<HistoryStore>()
+        .query(item_id.as_deref(), limit)
+        .map_err(|e| AppError::io(Message::ReadHistory, e))
+}
+
+// 获取每个启动项最近一次的运行结果
+#[tauri::command]
+fn get_last_run_results(app: AppHandle) -> Result<std::collections::HashMap<String, RunRecord>, AppError> {
+    app.state::<HistoryStore>()
+        .last_results()
+        .map_err(|e| AppError::io(Message::ReadHistory, e))
+}
+
+// 清理运行历史：删除已删除启动项的记录和早于older_than_days天的记录，每个启动项最多保留keep_per_item条
+#[tauri::command]
+fn prune_run_history(app: AppHandle, older_than_days: Option<u32>, keep_per_item: Option<usize>) -> Result<usize, AppError> {
+    let cutoff = older_than_days.map(|days| chrono::Local::now() - chrono::Duration::days(days as i64));
+    let items = read_startup_items(&app)?;
+    let removed = app.state::<HistoryStore>()
+        .prune(cutoff, keep_per_item, |id| items.iter().any(|item| item.id == id))
+        .map_err(|e| AppError::io(Message::PruneHistory, e))?;
+    info!("Pruned {} run history records", removed);
+    Ok(removed)
+}
+
+// 启用了保活策略的启动项交给监控器持有进程句柄，返回留给调用方的启动信息
+//
+// 进程句柄交出后调度器无法再等待其退出，依赖其“已退出”的启动项会被跳过。
+fn supervise_if_needed(app: &AppHandle, item: &StartupItem, launched: Launched) -> Launched {
+    if !item.restart.is_enabled() {
+        return launched;
+    }
+    
+    let (pid, exit) = (launched.pid, launched.exit);
+    app.state::<Supervisor>().supervise(item.clone(), launched);
+    Launched { pid, process: None, exit }
+}
+
+// 需要保活的启动项等待结束时失败退出，同样交给保活监控器按策略重启；
+// 等待超时或无法等待时进程可能仍在运行，不做处理以免重复启动
+fn supervise_failed_exit(app: &AppHandle, item: &StartupItem, error: &LaunchError) {
+    let LaunchError::Exit { code, exited: true, .. } = error else {
+        return;
+    };
+    if !item.restart.is_enabled() {
+        return;
+    }
+    
+    info!("Startup item '{}' exited with a failure, handing it to the supervisor", item.name);
+    let exit = ExitInfo { code: *code, success: false };
+    app.state::<Supervisor>().supervise(item.clone(), Launched { pid: None, process: None, exit: Some(exit) });
+}
+
+// 创建保活监控器，状态变化通过startup-item-status事件通知界面
+fn create_supervisor(app: &AppHandle) -> Result<Supervisor, AppError> {
+    let logs_dir = get_item_logs_dir(app)?;
+    let app_handle = app.clone();
+    Ok(Supervisor::new(
+        Arc::new(scheduler::SystemClock),
+        move |item: &StartupItem| run_startup_item(&SystemLauncher, item, &logs_dir),
+        move |status: &SupervisedStatus| {
+            if let Err(e) = app_handle.emit("startup-item-status", status) {
+                warn!("Failed to emit startup item status: {}", e);
+            }
+        },
+    ))
+}
+
+// 获取被保活监控的启动项状态
+#[tauri::command]
+fn get_supervised_items(app: AppHandle) -> Vec<SupervisedStatus> {
+    app.state::<Supervisor>().statuses()
+}
+
+// 停止监控启动项，不结束其进程
+#[tauri::command]
+fn stop_supervising(app: AppHandle, id: String) -> bool {
+    info!("Stop supervising startup item requested: {}", id);
+    app.state::<Supervisor>().stop(&id)
+}
+
+// 单独执行一个启动项：未启用时返回None，否则通过sleep等待设置的延迟后运行
+fn run_single_startup_item(
+    launcher: &dyn Launcher,
+    sleep: &dyn Fn(Duration),
+    item: &StartupItem,
+    logs_dir: &Path,
+) -> Option<Result<Launched, LaunchError>> {
     if !item.enabled {
         debug!("Startup item '{}' is disabled, skipping", item.name);
-        return Ok(());
+        return None;
     }
 
-    // 如果启用了延迟，先等待
-    if item.delay_enabled && item.delay_seconds > 0 {
+    let delay = item.delay();
+    if !delay.is_zero() {
         info!("Delaying startup item '{}' for {} seconds", item.name, item.delay_seconds);
-        thread::sleep(Duration::from_secs(item.delay_seconds as u64));
+        sleep(delay);
     }
 
-    match item.mode.as_str() {
-        "normal" => {
+    Some(run_startup_item(launcher, item, logs_dir))
+}
+
+// 运行单个启动项；要求等待结束时，等待进程退出并把输出和结果写入启动项日志
+fn run_startup_item(launcher: &dyn Launcher, item: &StartupItem, logs_dir: &Path) -> Result<Launched, LaunchError> {
+    if !item.wait_for_exit {
+        return launch_startup_item(launcher, item, None).map_err(LaunchError::Spawn);
+    }
+    
+    let mut log = ItemLog::open(logs_dir, &item.id)
+        .map_err(|e| LaunchError::Spawn(trf(Message::OpenItemLogFailed, &[&e])))?;
+    log.line(&format!("Starting '{}'", item.name));
+    
+    let mut launched = launch_startup_item(launcher, item, Some(&log)).map_err(|e| {
+        log.line(&format!("Failed to start: {}", e));
+        LaunchError::Spawn(e)
+    })?;
+    
+    let Some(process) = launched.process.as_mut() else {
+        log.line("No process handle available, cannot wait for exit");
+        return Err(LaunchError::Exit { message: tr(Message::NoProcessHandle).to_string(), code: None, exited: false });
+    };
+    
+    let timeout = (item.wait_timeout_seconds > 0)
+        .then(|| Duration::from_secs(item.wait_timeout_seconds as u64));
+    info!("Waiting for startup item '{}' to exit (timeout: {:?})", item.name, timeout);
+    
+    match process.wait_timeout(timeout) {
+        Ok(Some(exit)) => {
+            let code = exit.code.map_or("none".to_string(), |code| code.to_string());
+            log.line(&format!("Exited with code {}", code));
+            info!("Startup item '{}' exited with code {}", item.name, code);
+            launched.process = None;
+            launched.exit = Some(exit);
+            if exit.success {
+                Ok(launched)
+            } else {
+                Err(LaunchError::Exit { message: trf(Message::ExitCode, &[&code]), code: exit.code, exited: true })
+            }
+        }
+        Ok(None) => {
+            log.line(&format!("Still running after {} seconds, stopped waiting", item.wait_timeout_seconds));
+            warn!("Timed out waiting for startup item '{}' to exit", item.name);
+            Err(LaunchError::Exit {
+                message: trf(Message::WaitTimeout, &[&item.wait_timeout_seconds]),
+                code: None,
+                exited: false,
+            })
+        }
+        Err(e) => {
+            log.line(&format!("Failed to wait for exit: {}", e));
+            Err(LaunchError::Exit { message: trf(Message::WaitFailed, &[&e]), code: None, exited: false })
+        }
+    }
+}
+
+// 启动单个启动项，不处理启用状态和延迟；output不为空时将子进程的输出写入启动项日志
+fn launch_startup_item(launcher: &dyn Launcher, item: &StartupItem, output: Option<&ItemLog>) -> Result<Launched, String> {
+    let expanded;
+    let item = if item.expand_environment {
+        debug!("Expanding environment variables for startup item '{}'", item.name);
+        expanded = item.with_expanded_environment();
+        &expanded
+    } else {
+        item
+    };
+
+    let launched = match &item.launch {
+        Launch::Executable { path: executable_path, .. } => {
             debug!("Executing startup item '{}' in normal mode", item.name);
-            if item.executable_path.is_empty() {
+            if executable_path.is_empty() {
                 error!("Executable path is empty for startup item '{}'", item.name);
-                return Err("可执行文件路径为空".to_string());
+                return Err(tr(Message::EmptyExecutablePath).to_string());
             }
 
             if item.run_as_admin {
-                info!("Running startup item '{}' as administrator: {}", item.name, item.executable_path);
-                // 以管理员身份运行 - 使用Windows API直接启动，避免PowerShell
-                #[cfg(windows)]
-                {
-                    use std::ffi::OsStr;
-                    use std::os::windows::ffi::OsStrExt;
-                    
-                    unsafe {
-                        let exe_path_wide: Vec<u16> = OsStr::new(&item.executable_path).encode_wide().chain(std::iter::once(0)).collect();
-                        let verb_wide: Vec<u16> = OsStr::new("runas").encode_wide().chain(std::iter::once(0)).collect();
-                        
-                        let params_wide: Vec<u16> = if item.arguments.is_empty() {
-                            vec![0]
-                        } else {
-                            OsStr::new(&item.arguments).encode_wide().chain(std::iter::once(0)).collect()
-                        };
-                        
-                        let result = winapi::um::shellapi::ShellExecuteW(
-                            std::ptr::null_mut(),
-                            verb_wide.as_ptr(),
-                            exe_path_wide.as_ptr(),
-                            if params_wide.len() > 1 { params_wide.as_ptr() } else { std::ptr::null() },
-                            std::ptr::null(),
-                            winapi::um::winuser::SW_HIDE,
-                        );
-                        
-                        if result as isize <= 32 {
-                            error!("Failed to start program as administrator: {} (result: {})", item.executable_path, result as isize);
-                            return Err(format!("以管理员身份启动程序失败: {}", item.executable_path));
-                        } else {
-                            info!("Successfully started '{}' as administrator", item.name);
-                        }
-                    }
+                info!("Running startup item '{}' as administrator: {}", item.name, executable_path);
+                if !item.environment.is_empty() {
+                    // 提权后的进程由系统创建，无法继承这里设置的环境变量；保存时已拒绝，这里处理旧数据
+                    error!("Environment overrides cannot be applied to elevated startup item '{}'", item.name);
+                    return Err(trf(Message::ElevatedEnvironmentUnsupported, &[&item.name]));
                 }
-                
-                #[cfg(not(windows))]
-                {
-                    error!("Administrator startup not supported on non-Windows systems for item '{}'", item.name);
-                    return Err("非Windows系统不支持管理员权限启动".to_string());
+                if output.is_some() {
+                    debug!("Output of elevated startup item '{}' is not captured", item.name);
                 }
+                // 先按Windows规则解析再重新拼接，保证引号和反斜杠与普通启动时一致
+                let params = cmdline::join(&item.argv());
+                let launched = launcher.spawn_elevated(executable_path, &params, item.working_dir().as_deref())
+                    .map_err(|e| {
+                        error!("Failed to start program as administrator: {} ({})", executable_path, e);
+                        trf(Message::ElevatedLaunchFailed, &[&e])
+                    })?;
+                info!("Successfully started '{}' as administrator", item.name);
+                launched
             } else {
-                info!("Running startup item '{}' normally: {}", item.name, item.executable_path);
-                // 普通运行 - 直接启动，不使用PowerShell
-                let mut normal_cmd = Command::new(&item.executable_path);
-                if !item.arguments.is_empty() {
-                    debug!("Using arguments for '{}': {}", item.name, item.arguments);
-                    // 简单的参数分割，可能需要更复杂的解析
-                    let args: Vec<&str> = item.arguments.split_whitespace().collect();
-                    normal_cmd.args(args);
+                info!("Running startup item '{}' normally: {}", item.name, executable_path);
+                let argv = item.argv();
+                if !argv.is_empty() {
+                    debug!("Using arguments for '{}': {:?}", item.name, argv);
                 }
-                
-                // 在Windows上隐藏命令行窗口（如果启动的是控制台程序）
-                #[cfg(windows)]
-                {
-                    use std::os::windows::process::CommandExt;
-                    normal_cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+                let working_dir = item.working_dir();
+                if let Some(dir) = &working_dir {
+                    debug!("Using working directory for '{}': {}", item.name, dir.display());
                 }
                 
-                match normal_cmd.spawn() {
-                    Ok(_) => {
-                        info!("Successfully started startup item '{}'", item.name);
-                    }
-                    Err(e) => {
-                        error!("Failed to start startup item '{}': {}", item.name, e);
-                        return Err(format!("启动程序失败: {}", e));
-                    }
-                }
+                let options = SpawnOptions { working_dir, environment: &item.environment, output };
+                let launched = launcher.spawn(executable_path, &argv, &options).map_err(|e| {
+                    error!("Failed to start startup item '{}': {}", item.name, e);
+                    trf(Message::LaunchFailed, &[&e])
+                })?;
+                info!("Successfully started startup item '{}' (PID: {:?})", item.name, launched.pid);
+                launched
             }
-
-
         }
-        "command" => {
-            debug!("Executing startup item '{}' in command mode", item.name);
-            if item.command.is_empty() {
+        Launch::Shell { command, shell } => {
+            debug!("Executing startup item '{}' in command mode with {:?}", item.name, shell);
+            if command.is_empty() {
                 error!("Command is empty for startup item '{}'", item.name);
-                return Err("命令为空".to_string());
+                return Err(tr(Message::EmptyCommand).to_string());
             }
 
-            let mut cmd = if item.run_as_admin {
-                info!("Running command as administrator for '{}': {}", item.name, item.command);
-                // 以管理员身份运行命令
-                let mut admin_cmd = Command::new("powershell");
-                admin_cmd.args([
-                    "-ExecutionPolicy", "Bypass",
-                    "-Command",
-                    &format!("Start-Process powershell -ArgumentList @('-ExecutionPolicy', 'Bypass', '-Command', '{}') -Verb RunAs -ErrorAction Stop", 
-                        item.command.replace("'", "''"))
-                ]);
-                
-                // 在Windows上隐藏命令行窗口
-                #[cfg(windows)]
-                {
-                    use std::os::windows::process::CommandExt;
-                    admin_cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-                }
-                
-                admin_cmd
+            let launched = if item.run_as_admin {
+                info!("Running command as administrator for '{}': {}", item.name, command);
+                // 提权后的进程由系统创建，不会继承工作目录和环境变量，因此在命令开头设置
+                let script = format!("{}{}", shell.prelude(item.working_dir().as_deref(), &item.environment), command);
+                launcher.spawn_elevated(shell.program(), &shell.parameters(&script), None)
+                    .map_err(|e| {
+                        error!("Failed to execute command as administrator for '{}': {}", item.name, e);
+                        trf(Message::ElevatedCommandFailed, &[&e])
+                    })?
             } else {
-                info!("Running command normally for '{}': {}", item.name, item.command);
-                // 普通运行命令
-                let mut normal_cmd = Command::new("powershell");
-                normal_cmd.args(["-ExecutionPolicy", "Bypass", "-Command", &item.command]);
-                
-                // 在Windows上隐藏命令行窗口
-                #[cfg(windows)]
-                {
-                    use std::os::windows::process::CommandExt;
-                    normal_cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-                }
-                
-                normal_cmd
+                info!("Running command normally for '{}': {}", item.name, command);
+                let options = SpawnOptions { working_dir: item.working_dir(), environment: &item.environment, output };
+                launcher.run_shell(*shell, command, &options).map_err(|e| {
+                    error!("Failed to execute command for startup item '{}': {}", item.name, e);
+                    trf(Message::CommandFailed, &[&e])
+                })?
             };
+            
+            info!("Successfully executed command for startup item '{}'", item.name);
+            launched
+        }
+    };
 
-            match cmd.spawn() {
-                Ok(_) => {
-                    info!("Successfully executed command for startup item '{}'", item.name);
-                }
-                Err(e) => {
-                    error!("Failed to execute command for startup item '{}': {}", item.name, e);
-                    return Err(format!("执行命令失败: {}", e));
-                }
+    info!("Startup item '{}' executed successfully", item.name);
+    Ok(launched)
+}
+
+// 记录启动项调度进度
+fn log_startup_progress(progress: &scheduler::Progress) {
+    match progress {
+        scheduler::Progress::Scheduled { id, name, delay } => {
+            if !delay.is_zero() {
+                info!("Startup item '{}' (ID: {}) scheduled to start after {} seconds", name, id, delay.as_secs());
             }
         }
-        _ => {
-            error!("Unknown startup item mode '{}' for item '{}'", item.mode, item.name);
-            return Err(format!("未知的启动项模式: {}", item.mode));
+        scheduler::Progress::Started { id, name, elapsed } => {
+            info!("Starting startup item '{}' (ID: {}) at +{:.1}s", name, id, elapsed.as_secs_f64());
         }
+        scheduler::Progress::Retrying { id, name, attempt, delay, error } => {
+            warn!("Attempt {} of startup item '{}' (ID: {}) failed: {}; retrying in {} seconds",
+                  attempt, name, id, error, delay.as_secs());
+        }
+        scheduler::Progress::Finished { id, name, elapsed, status } => match status {
+            scheduler::Status::Succeeded => {
+                info!("Startup item '{}' (ID: {}) started successfully at +{:.1}s", name, id, elapsed.as_secs_f64());
+            }
+            scheduler::Status::Failed(e) => {
+                error!("Failed to execute startup item '{}' (ID: {}) at +{:.1}s: {}", name, id, elapsed.as_secs_f64(), e);
+            }
+            scheduler::Status::Skipped(reason) => {
+                warn!("Skipped startup item '{}' (ID: {}): {}", name, id, reason);
+            }
+        },
     }
-
-    info!("Startup item '{}' executed successfully", item.name);
-    Ok(())
 }
 
-// 执行所有启动项
+// 执行所有启动项，返回每个启动项的结果
 #[tauri::command]
-async fn execute_all_startup_items(app: AppHandle) -> Result<(), String> {
+async fn execute_all_startup_items(app: AppHandle) -> Result<StartupReport, AppError> {
+    run_all_startup_items(app, Trigger::Manual).await
+}
+
+// 执行所有启动项，trigger记录到运行历史中；进度通过startup-progress事件通知界面
+async fn run_all_startup_items(app: AppHandle, trigger: Trigger) -> Result<StartupReport, AppError> {
     info!("Starting execution of all startup items");
     let items = load_startup_items(app.clone()).await?;
-    
-    let enabled_items: Vec<_> = items.iter().filter(|item| item.enabled).collect();
-    info!("Found {} enabled startup items out of {} total items", enabled_items.len(), items.len());
-    
-    let mut success_count = 0;
-    let mut error_count = 0;
-    
-    for item in items {
-        if item.enabled {
-            if let Err(e) = execute_startup_item(item.clone()).await {
-                error!("Failed to execute startup item '{}': {}", item.name, e);
-                error_count += 1;
-                eprintln!("执行启动项 '{}' 失败: {}", item.name, e);
-                // 继续执行其他启动项，不因为一个失败而停止
-            } else {
-                success_count += 1;
+    let total_count = items.len();
+    
+    let enabled_items: Vec<_> = items.into_iter().filter(|item| item.enabled).collect();
+    info!("Found {} enabled startup items out of {} total items", enabled_items.len(), total_count);
+    
+    // 所有启动项并发调度，各自的延迟都从此刻开始计算；单个失败不影响其他启动项
+    let logs_dir = get_item_logs_dir(&app)?;
+    let launch_app = app.clone();
+    let progress_app = app.clone();
+    // 重试用尽后仍失败退出的启动项交给保活监控器，需要按ID找回启动项
+    let supervised: BTreeMap<String, StartupItem> = enabled_items
+        .iter()
+        .filter(|item| item.restart.is_enabled())
+        .map(|item| (item.id.clone(), item.clone()))
+        .collect();
+    let run_started_at = chrono::Local::now();
+    let outcomes = scheduler::run(
+        Arc::new(scheduler::SystemClock),
+        enabled_items,
+        move |item: &StartupItem| {
+            run_startup_item(&SystemLauncher, item, &logs_dir).map(|launched| supervise_if_needed(&launch_app, item, launched))
+        },
+        move |progress: scheduler::Progress| {
+            log_startup_progress(&progress);
+            if let scheduler::Progress::Finished { id, status: scheduler::Status::Failed(e), .. } = &progress {
+                if let Some(item) = supervised.get(id) {
+                    supervise_failed_exit(&progress_app, item, e);
+                }
+            }
+            if let Err(e) = progress_app.emit("startup-progress", ProgressEvent::from(&progress)) {
+                warn!("Failed to emit startup progress: {}", e);
             }
+        },
+    ).await;
+    
+    for outcome in &outcomes {
+        if let scheduler::Status::Failed(e) = &outcome.status {
+            eprintln!("{}", trf(Message::CliItemFailed, &[&outcome.name, &outcome.id, &outcome.attempts, e]));
         }
     }
     
-    info!("Startup items execution completed: {} successful, {} failed", success_count, error_count);
+    record_history(&app, outcomes.iter().map(|outcome| history_record(outcome, run_started_at, trigger)).collect());
+    
+    let report = StartupReport::from_outcomes(&outcomes);
+    info!("Startup items execution completed in {:.1}s: {} successful, {} failed, {} skipped",
+          report.elapsed_ms as f64 / 1000.0, report.succeeded, report.failed, report.skipped);
     
     // 检查是否需要在执行完启动项后退出
-    let settings = load_app_settings(app)?;
+    let settings = load_app_settings(app.clone())?;
     if settings.exit_after_startup {
+        // 有启动项需要保活时，等到它们都不再需要监控后再退出
+        let supervisor = app.state::<Supervisor>().inner().clone();
+        if supervisor.is_active() {
+            info!("Exit after startup is enabled, waiting for supervised startup items to stop");
+            supervisor.wait_idle().await;
+        }
         info!("Exit after startup is enabled, application will exit in 1 second");
         // 等待一小段时间确保所有启动项都已启动
-        thread::sleep(Duration::from_millis(1000));
+        tokio::time::sleep(Duration::from_millis(1000)).await;
         std::process::exit(0);
     }
     
-    Ok(())
+    Ok(report)
 }
 
 #[tauri::command]
-async fn get_executable_icon(app: AppHandle, executable_path: String) -> Result<Option<String>, String> {
+async fn get_executable_icon(app: AppHandle, executable_path: String) -> Result<Option<String>, AppError> {
     
     // 获取数据目录用于存储图标
     let data_dir = get_data_dir(&app)?;
@@ -840,7 +1483,7 @@ async fn get_executable_icon(app: AppHandle, executable_path: String) -> Result<
     // 确保图标目录存在
     if !icons_dir.exists() {
         fs::create_dir_all(&icons_dir)
-            .map_err(|e| format!("Failed to create icons directory: {}", e))?;
+            .map_err(|e| AppError::io(Message::CreateIconsDir, e))?;
     }
     
     // 生成图标文件名（基于可执行文件路径的哈希）
@@ -855,7 +1498,7 @@ async fn get_executable_icon(app: AppHandle, executable_path: String) -> Result<
     
     // 检查文件是否存在
     if !std::path::Path::new(&executable_path).exists() {
-        return Err(format!("Executable file not found: {}", executable_path));
+        return Err(AppError::NotFound(trf(Message::ExecutableNotFound, &[&executable_path])));
     }
     
     // 使用Windows API直接提取图标，避免PowerShell
@@ -893,11 +1536,6 @@ async fn get_executable_icon(app: AppHandle, executable_path: String) -> Result<
         }
     }
     
-    #[cfg(not(windows))]
-    {
-        return Ok(None);
-    }
-    
     Ok(None)
 }
 
@@ -906,6 +1544,22 @@ pub fn run() {
     // 检查命令行参数
     let args: Vec<String> = std::env::args().collect();
     let auto_mode = args.iter().any(|arg| arg == "--auto");
+    let data_dir_arg = data_dir::from_args(&args);
+    let cli_action = match bundle::parse_cli(&args) {
+        Ok(action) => action,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    #[cfg(all(not(windows), feature = "systemd"))]
+    let systemd_items = match autostart::systemd::parse_cli(&args) {
+        Ok(action) => action,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
 
     // 获取exe同级目录的log文件夹路径
     let log_dir = std::env::current_exe()
@@ -939,16 +1593,55 @@ pub fn run() {
             info!("EasiStartup application starting, version: {}", env!("CARGO_PKG_VERSION"));
             info!("Auto mode: {}", auto_mode);
             
+            app.manage(DataWarnings::default());
+            
+            let data_dir = resolve_data_dir(app.handle(), data_dir_arg)?;
+            info!("Data directory: {} ({:?})", data_dir.path.display(), data_dir.source);
+            data_dir::remember(&data_dir);
+            app.manage(data_dir);
+            
+            // 尽早确定语言，自动模式下的错误信息也需要使用
+            let language = load_app_settings(app.handle().clone())
+                .map(|settings| settings.language)
+                .unwrap_or_default();
+            i18n::set_current(Language::resolve(language));
+            info!("Language: {:?}", i18n::current());
+            
+            let supervisor = create_supervisor(app.handle())?;
+            app.manage(supervisor);
+            app.manage(HistoryStore::new(get_data_dir(app.handle())?.join("history.jsonl")));
+            
+            // 命令行导入导出，完成后直接退出，不显示托盘
+            if let Some(action) = cli_action {
+                std::process::exit(run_cli_action(app.handle(), action));
+            }
+            #[cfg(all(not(windows), feature = "systemd"))]
+            if let Some(action) = systemd_items {
+                std::process::exit(run_systemd_items_action(app.handle(), action));
+            }
+            
             // 如果是自动模式，执行所有启动项然后退出
             if auto_mode {
                 info!("Running in auto mode, executing startup items");
                 let app_handle = app.handle().clone();
                 tauri::async_runtime::spawn(async move {
-                    if let Err(e) = execute_all_startup_items(app_handle).await {
-                        error!("Failed to execute startup items in auto mode: {}", e);
-                        eprintln!("自动执行启动项失败: {}", e);
-                    } else {
-                        info!("All startup items executed successfully in auto mode");
+                    match run_all_startup_items(app_handle.clone(), Trigger::Auto).await {
+                        Ok(report) if report.failed == 0 => {
+                            info!("All startup items executed successfully in auto mode");
+                        }
+                        Ok(report) => {
+                            warn!("{} of {} startup items failed in auto mode", report.failed, report.items.len());
+                        }
+                        Err(e) => {
+                            error!("Failed to execute startup items in auto mode: {}", e);
+                            eprintln!("{}", trf(Message::CliAutoRunFailed, &[&e]));
+                        }
+                    }
+                    // 保活的启动项需要本进程持续运行
+                    let supervisor = app_handle.state::<Supervisor>().inner().clone();
+                    if supervisor.is_active() {
+                        info!("Keeping running in auto mode for supervised startup items");
+                        supervisor.wait_idle().await;
                     }
                     std::process::exit(0);
                 });
@@ -956,10 +1649,7 @@ pub fn run() {
             }
             
             // 创建托盘菜单
-            let startup_editor = MenuItem::with_id(app, "startup_editor", "启动项编辑", true, None::<&str>)?;
-            let settings = MenuItem::with_id(app, "settings", "应用设置", true, None::<&str>)?;
-            let quit = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&startup_editor, &settings, &quit])?;
+            let menu = build_tray_menu(app.handle())?;
             info!("Tray menu created successfully");
 
             // 创建托盘图标
@@ -995,6 +1685,7 @@ pub fn run() {
                 })
 
                 .build(app)?;
+            update_window_titles(app.handle());
 
             Ok(())
         })
@@ -1019,6 +1710,11 @@ pub fn run() {
             get_shortcut_info,
             execute_startup_item,
             execute_all_startup_items,
+            get_supervised_items,
+            stop_supervising,
+            get_run_history,
+            get_last_run_results,
+            prune_run_history,
             load_app_settings,
             save_app_settings,
             apply_startup_settings,
@@ -1026,10 +1722,270 @@ pub fn run() {
             request_admin_restart,
             check_normal_startup,
             check_admin_startup,
+            get_admin_task_info,
             check_startup_reminders,
             update_reminder_settings,
-            get_app_version
+            get_app_version,
+            take_data_warnings,
+            get_data_dir_info,
+            open_data_dir,
+            select_bundle_file,
+            export_startup_items,
+            import_startup_items,
+            list_system_startup_entries,
+            import_system_startup_entries
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autostart::memory::MemoryAutostart;
+    use launcher::recording::{Call, RecordingLauncher};
+
+    fn executable(path: &str, arguments: &str) -> StartupItem {
+        StartupItem {
+            name: "test".to_string(),
+            launch: Launch::Executable { path: path.to_string(), arguments: arguments.to_string(), args: None },
+            ..StartupItem::default()
+        }
+    }
+
+    fn command(command: &str, shell: Shell) -> StartupItem {
+        StartupItem {
+            name: "test".to_string(),
+            launch: Launch::Shell { command: command.to_string(), shell },
+            ..StartupItem::default()
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    // 运行启动项，返回结果和请求等待的延迟
+    fn run(launcher: &RecordingLauncher, item: &StartupItem) -> (Option<Result<Launched, LaunchError>>, Vec<Duration>) {
+        let sleeps = Mutex::new(Vec::new());
+        let logs_dir = std::env::temp_dir().join(format!("easistartup-test-{}", Uuid::new_v4()));
+        let result = run_single_startup_item(launcher, &|delay| sleeps.lock().unwrap().push(delay), item, &logs_dir);
+        let _ = fs::remove_dir_all(&logs_dir);
+        (result, sleeps.into_inner().unwrap())
+    }
+
+    fn spawn_error(result: Option<Result<Launched, LaunchError>>) -> String {
+        match result {
+            Some(Err(LaunchError::Spawn(message))) => message,
+            other => panic!("expected a spawn error, got {:?}", other.map(|result| result.map(|launched| launched.pid))),
+        }
+    }
+
+    #[test]
+    fn disabled_items_are_skipped() {
+        let launcher = RecordingLauncher::new();
+        let item = StartupItem { enabled: false, delay_enabled: true, ..executable("app.exe", "") };
+
+        let (result, sleeps) = run(&launcher, &item);
+        assert!(result.is_none());
+        assert!(sleeps.is_empty());
+        assert!(launcher.calls().is_empty());
+    }
+
+    #[test]
+    fn delay_is_waited_before_launch() {
+        let launcher = RecordingLauncher::new();
+        let item = StartupItem { delay_enabled: true, delay_seconds: 30, ..executable("app.exe", "") };
+
+        let (result, sleeps) = run(&launcher, &item);
+        assert_eq!(result.unwrap().unwrap().pid, Some(1));
+        assert_eq!(sleeps, vec![Duration::from_secs(30)]);
+        assert_eq!(launcher.calls().len(), 1);
+    }
+
+    #[test]
+    fn disabled_delay_is_not_waited() {
+        let launcher = RecordingLauncher::new();
+        let item = StartupItem { delay_enabled: false, delay_seconds: 30, ..executable("app.exe", "") };
+
+        let (result, sleeps) = run(&launcher, &item);
+        assert!(result.unwrap().is_ok());
+        assert!(sleeps.is_empty());
+    }
+
+    #[test]
+    fn empty_path_or_command_is_rejected() {
+        let launcher = RecordingLauncher::new();
+
+        let (result, _) = run(&launcher, &executable("", "a b"));
+        assert_eq!(spawn_error(result), tr(Message::EmptyExecutablePath));
+        let (result, _) = run(&launcher, &command("", Shell::Sh));
+        assert_eq!(spawn_error(result), tr(Message::EmptyCommand));
+        assert!(launcher.calls().is_empty());
+    }
+
+    #[test]
+    fn unknown_mode_is_rejected_when_loading() {
+        let value = serde_json::json!({ "id": "a", "name": "test", "mode": "script", "command": "echo" });
+        assert!(serde_json::from_value::<StartupItem>(value).is_err());
+
+        let value = serde_json::json!({ "id": "a", "name": "test", "mode": "command", "command": "echo" });
+        assert!(serde_json::from_value::<StartupItem>(value).is_ok());
+    }
+
+    #[test]
+    fn arguments_are_split_like_windows() {
+        let launcher = RecordingLauncher::new();
+        let item = StartupItem { use_executable_directory: true, ..executable("/opt/app/run", r#"--name "a b" c\"d"#) };
+
+        let (result, _) = run(&launcher, &item);
+        assert!(result.unwrap().is_ok());
+        assert_eq!(launcher.calls(), vec![Call::Spawn {
+            program: "/opt/app/run".to_string(),
+            args: strings(&["--name", "a b", r#"c"d"#]),
+            working_dir: Some(PathBuf::from("/opt/app")),
+            environment: BTreeMap::new(),
+            captured: false,
+        }]);
+    }
+
+    #[test]
+    fn structured_args_take_precedence() {
+        let launcher = RecordingLauncher::new();
+        let item = StartupItem {
+            launch: Launch::Executable {
+                path: "app.exe".to_string(),
+                arguments: "ignored".to_string(),
+                args: Some(strings(&["a b", r"C:\dir\"])),
+            },
+            ..executable("", "")
+        };
+
+        run(&launcher, &item);
+        assert!(matches!(&launcher.calls()[..], [Call::Spawn { args, .. }] if *args == strings(&["a b", r"C:\dir\"])));
+    }
+
+    #[test]
+    fn elevated_arguments_are_rejoined() {
+        let launcher = RecordingLauncher::new();
+        let item = StartupItem { run_as_admin: true, working_directory: "C:\\work".to_string(), ..executable("app.exe", r#""a b"  c"#) };
+
+        run(&launcher, &item);
+        assert_eq!(launcher.calls(), vec![Call::Elevated {
+            program: "app.exe".to_string(),
+            parameters: r#""a b" c"#.to_string(),
+            directory: Some(PathBuf::from("C:\\work")),
+        }]);
+    }
+
+    #[test]
+    fn elevated_environment_is_rejected() {
+        let launcher = RecordingLauncher::new();
+        let mut item = StartupItem { run_as_admin: true, ..executable("app.exe", "") };
+        item.environment.insert("KEY".to_string(), Some("value".to_string()));
+
+        let (result, _) = run(&launcher, &item);
+        assert_eq!(spawn_error(result), trf(Message::ElevatedEnvironmentUnsupported, &[&"test"]));
+        assert!(launcher.calls().is_empty());
+        assert!(validate_launch_options(&[item]).is_err());
+    }
+
+    #[test]
+    fn commands_run_through_the_shell() {
+        let launcher = RecordingLauncher::new();
+        let mut item = command("echo $KEY", Shell::Bash);
+        item.environment.insert("KEY".to_string(), Some("value".to_string()));
+
+        run(&launcher, &item);
+        assert_eq!(launcher.calls(), vec![Call::Shell {
+            shell: Shell::Bash,
+            script: "echo $KEY".to_string(),
+            working_dir: None,
+            environment: item.environment.clone(),
+            captured: false,
+        }]);
+    }
+
+    #[test]
+    fn expanded_environment_applies_to_path_and_arguments() {
+        let launcher = RecordingLauncher::new();
+        let mut item = StartupItem { expand_environment: true, ..executable("%APP_DIR%/app", "--config ${APP_DIR}/app.ini") };
+        item.environment.insert("APP_DIR".to_string(), Some("/opt/app".to_string()));
+
+        run(&launcher, &item);
+        assert!(matches!(&launcher.calls()[..], [Call::Spawn { program, args, .. }]
+            if program == "/opt/app/app" && *args == strings(&["--config", "/opt/app/app.ini"])));
+    }
+
+    #[test]
+    fn launcher_failures_are_spawn_errors() {
+        let launcher = RecordingLauncher::failing("denied");
+
+        let (result, _) = run(&launcher, &executable("app.exe", ""));
+        assert_eq!(spawn_error(result), trf(Message::LaunchFailed, &[&"denied"]));
+    }
+
+    #[test]
+    fn waiting_without_a_process_handle_fails() {
+        let launcher = RecordingLauncher::new();
+        let item = StartupItem { wait_for_exit: true, ..executable("app.exe", "") };
+
+        let (result, _) = run(&launcher, &item);
+        assert!(matches!(result, Some(Err(LaunchError::Exit { code: None, exited: false, .. }))));
+        assert!(matches!(&launcher.calls()[..], [Call::Spawn { captured: true, .. }]));
+    }
+
+    fn autostart_settings(enabled: bool, as_admin: bool) -> AppSettings {
+        AppSettings { auto_startup_enabled: enabled, auto_startup_as_admin: as_admin, ..AppSettings::default() }
+    }
+
+    fn registered_command() -> AutostartCommand {
+        AutostartCommand { program: "easistartup".to_string(), args: strings(&["--auto"]) }
+    }
+
+    // 先启用另一种自启动，确认应用设置时会将其取消
+    fn memory_autostart(normal: bool, admin: bool) -> MemoryAutostart {
+        let backend = MemoryAutostart::default();
+        backend.set_normal(&registered_command(), normal).unwrap();
+        backend.set_admin(&registered_command(), admin).unwrap();
+        backend
+    }
+
+    #[test]
+    fn normal_autostart_replaces_admin() {
+        let backend = memory_autostart(false, true);
+        update_autostart(&backend, &registered_command(), &autostart_settings(true, false), &|| Ok(false)).unwrap();
+
+        assert_eq!(backend.normal(), Some(registered_command()));
+        assert_eq!(backend.admin(), None);
+    }
+
+    #[test]
+    fn admin_autostart_replaces_normal() {
+        let backend = memory_autostart(true, false);
+        update_autostart(&backend, &registered_command(), &autostart_settings(true, true), &|| Ok(true)).unwrap();
+
+        assert_eq!(backend.normal(), None);
+        assert_eq!(backend.admin(), Some(registered_command()));
+    }
+
+    #[test]
+    fn admin_autostart_requires_elevation() {
+        let backend = memory_autostart(true, false);
+        let result = update_autostart(&backend, &registered_command(), &autostart_settings(true, true), &|| Ok(false));
+
+        assert!(matches!(result, Err(AppError::Elevation(_))));
+        assert_eq!(backend.normal(), Some(registered_command()));
+        assert_eq!(backend.admin(), None);
+    }
+
+    #[test]
+    fn disabling_removes_both() {
+        let backend = memory_autostart(true, true);
+        let is_admin = || -> Result<bool, AppError> { panic!("not needed when disabling") };
+        update_autostart(&backend, &registered_command(), &autostart_settings(false, true), &is_admin).unwrap();
+
+        assert!(!backend.is_normal_enabled().unwrap());
+        assert!(!backend.is_admin_enabled().unwrap());
+    }
+}
@@ -0,0 +1,89 @@
+// 已启动进程的句柄，统一普通启动的子进程和以管理员身份启动的进程
+
+use std::io;
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+// 轮询子进程是否退出的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub enum ProcessHandle {
+    // 通过std::process::Command启动的子进程
+    Child(Child),
+    // 通过ShellExecuteExW启动的进程
+    #[cfg(windows)]
+    Raw(std::os::windows::io::OwnedHandle),
+}
+
+// 进程退出结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitInfo {
+    // 退出码，在Unix上被信号终止时为None
+    pub code: Option<i32>,
+    pub success: bool,
+}
+
+impl ProcessHandle {
+    // 进程ID，只有以管理员身份启动时需要从句柄获取
+    #[cfg(windows)]
+    pub fn id(&self) -> Option<u32> {
+        match self {
+            ProcessHandle::Child(child) => Some(child.id()),
+            #[cfg(windows)]
+            ProcessHandle::Raw(handle) => {
+                use std::os::windows::io::AsRawHandle;
+                let pid = unsafe { winapi::um::processthreadsapi::GetProcessId(handle.as_raw_handle() as _) };
+                (pid != 0).then_some(pid)
+            }
+        }
+    }
+
+    // 等待进程退出，timeout为None时一直等待；超时返回Ok(None)
+    pub fn wait_timeout(&mut self, timeout: Option<Duration>) -> io::Result<Option<ExitInfo>> {
+        match self {
+            ProcessHandle::Child(child) => {
+                let Some(timeout) = timeout else {
+                    let status = child.wait()?;
+                    return Ok(Some(ExitInfo { code: status.code(), success: status.success() }));
+                };
+
+                let deadline = Instant::now() + timeout;
+                loop {
+                    if let Some(status) = child.try_wait()? {
+                        return Ok(Some(ExitInfo { code: status.code(), success: status.success() }));
+                    }
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Ok(None);
+                    }
+                    std::thread::sleep(POLL_INTERVAL.min(deadline - now));
+                }
+            }
+            #[cfg(windows)]
+            ProcessHandle::Raw(handle) => {
+                use std::os::windows::io::AsRawHandle;
+                use winapi::um::processthreadsapi::GetExitCodeProcess;
+                use winapi::um::synchapi::WaitForSingleObject;
+                use winapi::um::winbase::{INFINITE, WAIT_OBJECT_0};
+                use winapi::shared::winerror::WAIT_TIMEOUT;
+
+                let raw = handle.as_raw_handle() as winapi::um::winnt::HANDLE;
+                let millis = timeout.map_or(INFINITE, |timeout| {
+                    timeout.as_millis().min((INFINITE - 1) as u128) as u32
+                });
+
+                match unsafe { WaitForSingleObject(raw, millis) } {
+                    WAIT_OBJECT_0 => {
+                        let mut code = 0u32;
+                        if unsafe { GetExitCodeProcess(raw, &mut code) } == 0 {
+                            return Err(io::Error::last_os_error());
+                        }
+                        Ok(Some(ExitInfo { code: Some(code as i32), success: code == 0 }))
+                    }
+                    WAIT_TIMEOUT => Ok(None),
+                    _ => Err(io::Error::last_os_error()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// 执行所有启动项的结果报告，以及发给界面的进度事件
+
+use crate::history::RunStatus;
+use crate::scheduler::{LaunchError, Outcome, Progress, SkipReason, Status};
+use serde::Serialize;
+use std::time::Duration;
+
+// 失败或跳过的原因类别，便于界面区分显示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    // 无法启动进程
+    Spawn,
+    // 进程已启动，但退出码非0或等待超时
+    Exit,
+    // 依赖的启动项没有满足条件
+    Dependency,
+    // 存在循环依赖
+    Cycle,
+}
+
+// 单个启动项的执行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemReport {
+    pub id: String,
+    pub name: String,
+    pub status: RunStatus,
+    pub error_kind: Option<ErrorKind>,
+    pub error: Option<String>,
+    // 从运行开始到启动完成经过的毫秒数
+    pub elapsed_ms: u64,
+    pub attempts: u32,
+}
+
+// 执行所有启动项的汇总报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    pub items: Vec<ItemReport>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub elapsed_ms: u64,
+}
+
+// 通过startup-progress事件发给界面的进度
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum ProgressEvent {
+    #[serde(rename_all = "camelCase")]
+    Scheduled { id: String, name: String, delay_ms: u64 },
+    #[serde(rename_all = "camelCase")]
+    Started { id: String, name: String, elapsed_ms: u64 },
+    #[serde(rename_all = "camelCase")]
+    Retrying { id: String, name: String, attempt: u32, delay_ms: u64, error: String },
+    #[serde(rename_all = "camelCase")]
+    Finished {
+        id: String,
+        name: String,
+        elapsed_ms: u64,
+        status: RunStatus,
+        error_kind: Option<ErrorKind>,
+        error: Option<String>,
+    },
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+// 拆分最终状态为结果、原因类别和错误信息
+fn describe(status: &Status) -> (RunStatus, Option<ErrorKind>, Option<String>) {
+    match status {
+        Status::Succeeded => (RunStatus::Succeeded, None, None),
+        Status::Failed(e) => {
+            let kind = match e {
+                LaunchError::Spawn(_) => ErrorKind::Spawn,
+                LaunchError::Exit { .. } => ErrorKind::Exit,
+            };
+            (RunStatus::Failed, Some(kind), Some(e.to_string()))
+        }
+        Status::Skipped(reason) => {
+            let kind = match reason {
+                SkipReason::Cycle => ErrorKind::Cycle,
+                SkipReason::Dependency { .. } => ErrorKind::Dependency,
+            };
+            (RunStatus::Skipped, Some(kind), Some(reason.to_string()))
+        }
+    }
+}
+
+impl From<&Outcome> for ItemReport {
+    fn from(outcome: &Outcome) -> Self {
+        let (status, error_kind, error) = describe(&outcome.status);
+        ItemReport {
+            id: outcome.id.clone(),
+            name: outcome.name.clone(),
+            status,
+            error_kind,
+            error,
+            elapsed_ms: millis(outcome.elapsed),
+            attempts: outcome.attempts,
+        }
+    }
+}
+
+impl StartupReport {
+    pub fn from_outcomes(outcomes: &[Outcome]) -> StartupReport {
+        let items: Vec<ItemReport> = outcomes.iter().map(ItemReport::from).collect();
+        let count = |status: RunStatus| items.iter().filter(|item| item.status == status).count();
+
+        StartupReport {
+            succeeded: count(RunStatus::Succeeded),
+            failed: count(RunStatus::Failed),
+            skipped: count(RunStatus::Skipped),
+            elapsed_ms: outcomes.iter().map(|outcome| millis(outcome.elapsed)).max().unwrap_or(0),
+            items,
+        }
+    }
+}
+
+impl From<&Progress> for ProgressEvent {
+    fn from(progress: &Progress) -> Self {
+        match progress {
+            Progress::Scheduled { id, name, delay } => ProgressEvent::Scheduled {
+                id: id.clone(),
+                name: name.clone(),
+                delay_ms: millis(*delay),
+            },
+            Progress::Started { id, name, elapsed } => ProgressEvent::Started {
+                id: id.clone(),
+                name: name.clone(),
+                elapsed_ms: millis(*elapsed),
+            },
+            Progress::Retrying { id, name, attempt, delay, error } => ProgressEvent::Retrying {
+                id: id.clone(),
+                name: name.clone(),
+                attempt: *attempt,
+                delay_ms: millis(*delay),
+                error: error.clone(),
+            },
+            Progress::Finished { id, name, elapsed, status } => {
+                let (status, error_kind, error) = describe(status);
+                ProgressEvent::Finished {
+                    id: id.clone(),
+                    name: name.clone(),
+                    elapsed_ms: millis(*elapsed),
+                    status,
+                    error_kind,
+                    error,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+// 启动项失败后的重试策略
+
+use crate::scheduler::LaunchError;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// 重试间隔的增长方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Backoff {
+    // 每次等待相同的时间
+    #[default]
+    Fixed,
+    // 每次等待时间翻倍，不超过设定的上限
+    Exponential,
+}
+
+impl Backoff {
+    // 第attempt次（从1开始）等待的时间，指数退避时不超过max_seconds
+    pub fn delay(self, base_seconds: u32, max_seconds: u32, attempt: u32) -> Duration {
+        let base = base_seconds as u64;
+        let seconds = match self {
+            Backoff::Fixed => base,
+            Backoff::Exponential => {
+                let factor = 1u64.checked_shl(attempt.saturating_sub(1)).unwrap_or(u64::MAX);
+                base.saturating_mul(factor).min(max_seconds.max(base_seconds) as u64)
+            }
+        };
+        Duration::from_secs(seconds)
+    }
+}
+
+// 哪些失败需要重试
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RetryOn {
+    // 只在无法启动进程时重试，例如网络驱动器尚未连接
+    #[default]
+    SpawnError,
+    // 进程启动后退出码非0或等待超时也重试
+    AnyFailure,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetryPolicy {
+    // 最多尝试的次数（包括第一次），1表示不重试
+    pub max_attempts: u32,
+    pub backoff: Backoff,
+    // 第一次重试前等待的秒数
+    pub delay_seconds: u32,
+    // 指数退避时单次等待的上限
+    pub max_delay_seconds: u32,
+    pub retry_on: RetryOn,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            backoff: Backoff::Fixed,
+            delay_seconds: 5,
+            max_delay_seconds: 60,
+            retry_on: RetryOn::SpawnError,
+        }
+    }
+}
+
+impl RetryPolicy {
+    // 第attempt次尝试（从1开始）失败后，是否需要再次尝试
+    pub fn should_retry(&self, attempt: u32, error: &LaunchError) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        match (self.retry_on, error) {
+            (_, LaunchError::Spawn(_)) => true,
+            (RetryOn::AnyFailure, LaunchError::Exit { .. }) => true,
+            (RetryOn::SpawnError, LaunchError::Exit { .. }) => false,
+        }
+    }
+
+    // 第attempt次尝试（从1开始）失败后，下一次尝试前等待的时间
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        self.backoff.delay(self.delay_seconds, self.max_delay_seconds, attempt)
+    }
+}
@@ -0,0 +1,90 @@
+// 外部命令的执行
+//
+// 调用systemctl、schtasks等命令的代码通过CommandRunner执行，测试时可以替换为不启动进程的实现。
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+// 测试中代替真实的命令执行
+#[cfg(test)]
+pub mod recording;
+
+// 命令的退出码和输出
+#[derive(Debug, Clone, Default)]
+pub struct Output {
+    // 被信号终止时没有退出码
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Output {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+pub trait CommandRunner {
+    // 执行命令并等待结束；stdin不为None时写入标准输入
+    fn run(&self, program: &str, args: &[&str], stdin: Option<&[u8]>) -> io::Result<Output>;
+}
+
+// 直接启动进程，Windows上不显示命令行窗口
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &str, args: &[&str], stdin: Option<&[u8]>) -> io::Result<Output> {
+        let mut cmd = Command::new(program);
+        cmd.args(args)
+            .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let mut child = cmd.spawn()?;
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(input)?;
+        }
+        let output = child.wait_with_output()?;
+
+        Ok(Output {
+            code: output.status.code(),
+            stdout: decode(&output.stdout),
+            stderr: decode(&output.stderr),
+        })
+    }
+}
+
+// Windows上控制台程序的输出被重定向时使用OEM代码页，例如中文系统上的GBK
+#[cfg(windows)]
+fn decode(bytes: &[u8]) -> String {
+    use winapi::um::stringapiset::MultiByteToWideChar;
+    use winapi::um::winnls::CP_OEMCP;
+
+    let Ok(len) = i32::try_from(bytes.len()) else {
+        return String::from_utf8_lossy(bytes).to_string();
+    };
+    if len == 0 {
+        return String::new();
+    }
+
+    unsafe {
+        let size = MultiByteToWideChar(CP_OEMCP, 0, bytes.as_ptr() as _, len, std::ptr::null_mut(), 0);
+        if size <= 0 {
+            return String::from_utf8_lossy(bytes).to_string();
+        }
+        let mut wide = vec![0u16; size as usize];
+        let written = MultiByteToWideChar(CP_OEMCP, 0, bytes.as_ptr() as _, len, wide.as_mut_ptr(), size);
+        String::from_utf16_lossy(&wide[..written.max(0) as usize])
+    }
+}
+
+#[cfg(not(windows))]
+fn decode(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_string()
+}
@@ -0,0 +1,48 @@
+// 只记录调用、不启动进程的CommandRunner，用于测试调用外部命令的逻辑
+
+use super::{CommandRunner, Output};
+use std::io;
+use std::sync::Mutex;
+
+type RespondFn = dyn Fn(&str, &[&str]) -> Output + Send + Sync;
+
+pub struct RecordingRunner {
+    // 每次调用的程序和参数，以空格连接
+    calls: Mutex<Vec<String>>,
+    respond: Box<RespondFn>,
+}
+
+impl RecordingRunner {
+    // 所有命令都以退出码0结束、没有输出
+    pub fn new() -> RecordingRunner {
+        RecordingRunner::responding(|_, _| Output { code: Some(0), ..Output::default() })
+    }
+
+    // 由respond根据程序和参数决定命令的结果
+    pub fn responding(respond: impl Fn(&str, &[&str]) -> Output + Send + Sync + 'static) -> RecordingRunner {
+        RecordingRunner { calls: Mutex::new(Vec::new()), respond: Box::new(respond) }
+    }
+
+    // 按顺序返回记录的调用
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl Default for RecordingRunner {
+    fn default() -> Self {
+        RecordingRunner::new()
+    }
+}
+
+impl CommandRunner for RecordingRunner {
+    fn run(&self, program: &str, args: &[&str], _stdin: Option<&[u8]>) -> io::Result<Output> {
+        let mut call = program.to_string();
+        for arg in args {
+            call.push(' ');
+            call.push_str(arg);
+        }
+        self.calls.lock().unwrap().push(call);
+        Ok((self.respond)(program, args))
+    }
+}
@@ -0,0 +1,762 @@
+// 启动项调度：按各自的延迟并发启动，延迟从本次运行开始时计算；
+// 有依赖的启动项会等到所有前置启动项满足条件后才启动
+
+use crate::i18n::{tr, trf, Message};
+use crate::process::{ExitInfo, ProcessHandle};
+use crate::StartupItem;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+
+// 测试中代替真实时钟
+#[cfg(test)]
+pub mod manual_clock;
+
+// 轮询端口和窗口的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// 时钟抽象，便于在测试中替换为可手动推进的时钟
+pub trait Clock: Send + Sync + 'static {
+    fn now(&self) -> Instant;
+    fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+// 基于tokio定时器的系统时钟
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep_until(deadline.into()))
+    }
+}
+
+// 启动项依赖的前置启动项需要满足的条件
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DependencyCondition {
+    // 前置启动项已成功启动
+    #[default]
+    Started,
+    // 前置启动项已退出且退出码为0
+    Exited,
+    // 前置启动项的进程已显示窗口
+    WindowVisible,
+    // 本机端口已可以连接
+    PortOpen { port: u16 },
+}
+
+// 启动成功后得到的进程信息
+pub struct Launched {
+    pub pid: Option<u32>,
+    // 可以等待退出的进程句柄，无法获得句柄或已等待结束时为None
+    pub process: Option<ProcessHandle>,
+    // 启动项要求等待结束时的退出结果
+    pub exit: Option<ExitInfo>,
+}
+
+// 启动失败的原因，决定是否按重试策略再次尝试
+#[derive(Debug, Clone)]
+pub enum LaunchError {
+    // 无法启动进程
+    Spawn(String),
+    // 进程已启动，但退出码非0、等待超时或无法等待其结束；code为进程的退出码，
+    // exited表示进程确实已经退出，等待超时或无法等待时进程可能仍在运行
+    Exit { message: String, code: Option<i32>, exited: bool },
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchError::Spawn(message) | LaunchError::Exit { message, .. } => f.write_str(message),
+        }
+    }
+}
+
+// 调度进度
+#[derive(Debug, Clone)]
+pub enum Progress {
+    // 已加入计划，将在运行开始后delay时启动
+    Scheduled { id: String, name: String, delay: Duration },
+    // 到达启动时间，开始启动
+    Started { id: String, name: String, elapsed: Duration },
+    // 第attempt次尝试失败，将在delay后重试
+    Retrying { id: String, name: String, attempt: u32, delay: Duration, error: String },
+    // 启动完成
+    Finished { id: String, name: String, elapsed: Duration, status: Status },
+}
+
+// 单个启动项的最终状态
+#[derive(Debug, Clone)]
+pub enum Status {
+    Succeeded,
+    Failed(LaunchError),
+    // 依赖未满足或存在循环依赖，没有启动
+    Skipped(SkipReason),
+}
+
+// 启动项被跳过的原因
+#[derive(Debug, Clone)]
+pub enum SkipReason {
+    // 位于循环依赖中，或依赖于循环中的启动项
+    Cycle,
+    // 依赖的启动项没有满足条件
+    Dependency { id: String, reason: String },
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Cycle => f.write_str(tr(Message::SkipCycle)),
+            SkipReason::Dependency { id, reason } => f.write_str(&trf(Message::SkipDependency, &[id, reason])),
+        }
+    }
+}
+
+// 单个启动项的调度结果
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub id: String,
+    pub name: String,
+    // 从运行开始到启动完成经过的时间
+    pub elapsed: Duration,
+    // 从运行开始到开始启动经过的时间（含延迟和等待依赖），未启动时为None
+    pub started: Option<Duration>,
+    // 实际尝试启动的次数，未启动时为0
+    pub attempts: u32,
+    // 最后一次尝试是否创建了进程，以及得到的进程ID和退出码
+    pub spawned: bool,
+    pub pid: Option<u32>,
+    pub exit_code: Option<i32>,
+    pub status: Status,
+}
+
+// 启动项在本次运行中的状态，供依赖它的启动项等待
+#[derive(Debug, Clone, PartialEq)]
+enum State {
+    Pending,
+    Started { pid: Option<u32> },
+    Exited { success: bool },
+    // 启动失败、被跳过，或无法得知退出结果
+    Failed,
+}
+
+// 查找启动项之间的循环依赖，返回位于循环中或依赖于循环的启动项ID
+//
+// 依赖不在列表中的启动项（例如已删除）不参与检测。
+pub fn find_cycle(items: &[StartupItem]) -> Option<Vec<String>> {
+    let blocked = blocked_by_cycle(items);
+    if blocked.is_empty() {
+        return None;
+    }
+
+    Some(
+        items
+            .iter()
+            .filter(|item| blocked.contains(item.id.as_str()))
+            .map(|item| item.id.clone())
+            .collect(),
+    )
+}
+
+// 使用Kahn算法做拓扑排序，返回无法排序（位于循环中或依赖循环）的启动项ID
+fn blocked_by_cycle(items: &[StartupItem]) -> HashSet<&str> {
+    let ids: HashSet<&str> = items.iter().map(|item| item.id.as_str()).collect();
+    let mut in_degree: HashMap<&str, usize> = HashMap::new();
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for item in items {
+        let prerequisites: HashSet<&str> = item
+            .depends_on
+            .iter()
+            .map(String::as_str)
+            .filter(|id| ids.contains(id))
+            .collect();
+        in_degree.insert(item.id.as_str(), prerequisites.len());
+        for prerequisite in prerequisites {
+            dependents.entry(prerequisite).or_default().push(item.id.as_str());
+        }
+    }
+
+    let mut queue: VecDeque<&str> = in_degree
+        .iter()
+        .filter(|(_, degree)| **degree == 0)
+        .map(|(id, _)| *id)
+        .collect();
+
+    while let Some(id) = queue.pop_front() {
+        for dependent in dependents.get(id).into_iter().flatten() {
+            let degree = in_degree.get_mut(dependent).expect("dependent is a known item");
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(dependent);
+            }
+        }
+    }
+
+    in_degree
+        .into_iter()
+        .filter(|(_, degree)| *degree > 0)
+        .map(|(id, _)| id)
+        .collect()
+}
+
+// 并发调度所有启动项，返回的结果与传入顺序一致
+//
+// launch是同步的启动函数（可能因UAC提示等阻塞），在阻塞线程池中执行。
+// 有依赖的启动项在依赖满足后、且不早于自身延迟到期时启动。
+pub async fn run<L, P>(
+    clock: Arc<dyn Clock>,
+    items: Vec<StartupItem>,
+    launch: L,
+    progress: P,
+) -> Vec<Outcome>
+where
+    L: Fn(&StartupItem) -> Result<Launched, LaunchError> + Send + Sync + 'static,
+    P: Fn(Progress) + Send + Sync + 'static,
+{
+    let start = clock.now();
+    let launch = Arc::new(launch);
+    let progress = Arc::new(progress);
+
+    let in_cycle: HashSet<String> = blocked_by_cycle(&items).into_iter().map(String::from).collect();
+
+    // 只有被要求“退出后”才启动的前置启动项才需要等待其退出
+    let awaited_exit: HashSet<String> = items
+        .iter()
+        .filter(|item| item.dependency_condition == DependencyCondition::Exited)
+        .flat_map(|item| item.depends_on.iter().cloned())
+        .collect();
+
+    let mut senders = HashMap::new();
+    let mut receivers = HashMap::new();
+    for item in &items {
+        let (tx, rx) = watch::channel(State::Pending);
+        senders.insert(item.id.clone(), tx);
+        receivers.insert(item.id.clone(), rx);
+    }
+
+    let mut tasks = JoinSet::new();
+
+    for (index, item) in items.into_iter().enumerate() {
+        let delay = item.delay();
+        progress(Progress::Scheduled {
+            id: item.id.clone(),
+            name: item.name.clone(),
+            delay,
+        });
+
+        let state = senders.remove(&item.id).unwrap_or_else(|| watch::channel(State::Pending).0);
+        // 不在本次运行中的前置启动项（已禁用或已删除）永远不会满足条件
+        let missing = item.depends_on.iter().find(|id| !receivers.contains_key(*id)).cloned();
+        let prerequisites: Vec<(String, watch::Receiver<State>)> = item
+            .depends_on
+            .iter()
+            .filter_map(|id| receivers.get(id).map(|rx| (id.clone(), rx.clone())))
+            .collect();
+        let blocked = in_cycle.contains(&item.id);
+        let wait_exit = awaited_exit.contains(&item.id);
+
+        let clock = clock.clone();
+        let launch = launch.clone();
+        let progress = progress.clone();
+        tasks.spawn(async move {
+            let (id, name) = (item.id.clone(), item.name.clone());
+            let mut attempts = 0;
+            let mut started = None;
+            let mut spawned = false;
+            let mut pid = None;
+            let mut exit_code = None;
+
+            let status = if blocked {
+                let _ = state.send(State::Failed);
+                Status::Skipped(SkipReason::Cycle)
+            } else if let Some(prerequisite) = missing {
+                log::warn!("Dependency '{}' of startup item '{}' is not part of this run", prerequisite, name);
+                let _ = state.send(State::Failed);
+                Status::Skipped(SkipReason::Dependency {
+                    id: prerequisite,
+                    reason: tr(Message::DependencyNotScheduled).to_string(),
+                })
+            } else {
+                let timeout = Duration::from_secs(item.dependency_timeout_seconds as u64);
+                let mut unmet = None;
+                for (prerequisite, rx) in prerequisites {
+                    if let Err(reason) =
+                        wait_for(&*clock, rx, &item.dependency_condition, timeout).await
+                    {
+                        unmet = Some(SkipReason::Dependency { id: prerequisite, reason });
+                        break;
+                    }
+                }
+
+                match unmet {
+                    Some(reason) => {
+                        let _ = state.send(State::Failed);
+                        Status::Skipped(reason)
+                    }
+                    None => {
+                        clock.sleep_until(start + delay).await;
+                        let elapsed = clock.now().saturating_duration_since(start);
+                        started = Some(elapsed);
+                        progress(Progress::Started { id: id.clone(), name: name.clone(), elapsed });
+
+                        let policy = item.retry;
+                        let result = loop {
+                            attempts += 1;
+                            let launch = launch.clone();
+                            let attempt_item = item.clone();
+                            let result = tokio::task::spawn_blocking(move || launch(&attempt_item))
+                                .await
+                                .unwrap_or_else(|e| Err(LaunchError::Spawn(trf(Message::TaskAborted, &[&e]))));
+
+                            match result {
+                                Err(error) if policy.should_retry(attempts, &error) => {
+                                    let delay = policy.delay_after(attempts);
+                                    progress(Progress::Retrying {
+                                        id: id.clone(),
+                                        name: name.clone(),
+                                        attempt: attempts,
+                                        delay,
+                                        error: error.to_string(),
+                                    });
+                                    clock.sleep_until(clock.now() + delay).await;
+                                }
+                                result => break result,
+                            }
+                        };
+
+                        match result {
+                            Ok(launched) => {
+                                spawned = true;
+                                pid = launched.pid;
+                                exit_code = launched.exit.and_then(|exit| exit.code);
+                                let _ = state.send(State::Started { pid: launched.pid });
+                                if let Some(exit) = launched.exit {
+                                    let _ = state.send(State::Exited { success: exit.success });
+                                } else if wait_exit {
+                                    watch_exit(launched.process, state);
+                                }
+                                Status::Succeeded
+                            }
+                            Err(e) => {
+                                if let LaunchError::Exit { code, .. } = &e {
+                                    spawned = true;
+                                    exit_code = *code;
+                                }
+                                let _ = state.send(State::Failed);
+                                Status::Failed(e)
+                            }
+                        }
+                    }
+                }
+            };
+
+            let elapsed = clock.now().saturating_duration_since(start);
+            progress(Progress::Finished {
+                id: id.clone(),
+                name: name.clone(),
+                elapsed,
+                status: status.clone(),
+            });
+
+            (index, Outcome { id, name, elapsed, started, attempts, spawned, pid, exit_code, status })
+        });
+    }
+
+    let mut outcomes = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(outcome) => outcomes.push(outcome),
+            Err(e) => log::error!("Startup scheduler task failed: {}", e),
+        }
+    }
+
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+// 在后台等待子进程退出并发布退出结果
+fn watch_exit(process: Option<ProcessHandle>, state: watch::Sender<State>) {
+    match process {
+        Some(mut process) => {
+            tokio::task::spawn_blocking(move || {
+                let success = matches!(process.wait_timeout(None), Ok(Some(exit)) if exit.success);
+                let _ = state.send(State::Exited { success });
+            });
+        }
+        None => {
+            // 无法获得进程句柄时不能确定退出结果，依赖它的启动项将被跳过
+            log::warn!("Cannot wait for exit of a process started without a handle");
+            let _ = state.send(State::Failed);
+        }
+    }
+}
+
+// 等待前置启动项满足条件
+//
+// 前置启动项尚未启动时一直等待（它最终总会启动、失败或被跳过），
+// timeout从前置启动项启动后开始计算。
+async fn wait_for(
+    clock: &dyn Clock,
+    mut rx: watch::Receiver<State>,
+    condition: &DependencyCondition,
+    timeout: Duration,
+) -> Result<(), String> {
+    let mut deadline = None;
+
+    loop {
+        let state = rx.borrow_and_update().clone();
+        let pid = match state {
+            State::Pending => {
+                if rx.changed().await.is_err() {
+                    return Err(tr(Message::DependencyStopped).to_string());
+                }
+                continue;
+            }
+            State::Failed => return Err(tr(Message::DependencyFailed).to_string()),
+            State::Exited { success: false } => return Err(tr(Message::DependencyExitFailed).to_string()),
+            State::Exited { success: true } => return Ok(()),
+            State::Started { pid } => pid,
+        };
+
+        let poll = match condition {
+            DependencyCondition::Started => return Ok(()),
+            DependencyCondition::Exited => false,
+            DependencyCondition::WindowVisible => {
+                if !cfg!(windows) {
+                    return Err(tr(Message::WindowUnsupported).to_string());
+                }
+                let Some(pid) = pid else {
+                    return Err(tr(Message::NoProcessId).to_string());
+                };
+                if process_has_visible_window(pid) {
+                    return Ok(());
+                }
+                true
+            }
+            DependencyCondition::PortOpen { port } => {
+                let port = *port;
+                let open = tokio::task::spawn_blocking(move || is_port_open(port))
+                    .await
+                    .unwrap_or(false);
+                if open {
+                    return Ok(());
+                }
+                true
+            }
+        };
+
+        let deadline = *deadline.get_or_insert_with(|| clock.now() + timeout);
+        if clock.now() >= deadline {
+            return Err(tr(Message::DependencyTimeout).to_string());
+        }
+
+        // 端口和窗口条件需要定时轮询，其余情况等待状态变化
+        let wake_at = if poll { deadline.min(clock.now() + POLL_INTERVAL) } else { deadline };
+
+        // 前置启动项的状态不会再变化（例如启动后不需要等待其退出），只能继续轮询
+        if rx.has_changed().is_err() {
+            if !poll {
+                return Err(tr(Message::DependencyStopped).to_string());
+            }
+            clock.sleep_until(wake_at).await;
+            continue;
+        }
+
+        tokio::select! {
+            changed = rx.changed() => {
+                if changed.is_err() && !poll {
+                    return Err(tr(Message::DependencyStopped).to_string());
+                }
+            }
+            _ = clock.sleep_until(wake_at) => {}
+        }
+    }
+}
+
+fn is_port_open(port: u16) -> bool {
+    let address = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    std::net::TcpStream::connect_timeout(&address, POLL_INTERVAL).is_ok()
+}
+
+// 检查进程是否有可见的顶层窗口
+#[cfg(windows)]
+fn process_has_visible_window(pid: u32) -> bool {
+    use winapi::shared::minwindef::{BOOL, LPARAM, TRUE, FALSE};
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::{EnumWindows, GetWindowThreadProcessId, IsWindowVisible};
+
+    struct Search {
+        pid: u32,
+        found: bool,
+    }
+
+    unsafe extern "system" fn callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = &mut *(lparam as *mut Search);
+        let mut window_pid = 0;
+        GetWindowThreadProcessId(hwnd, &mut window_pid);
+        if window_pid == search.pid && IsWindowVisible(hwnd) != 0 {
+            search.found = true;
+            return FALSE;
+        }
+        TRUE
+    }
+
+    let mut search = Search { pid, found: false };
+    unsafe {
+        EnumWindows(Some(callback), &mut search as *mut Search as LPARAM);
+    }
+    search.found
+}
+
+#[cfg(not(windows))]
+fn process_has_visible_window(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retry::{Backoff, RetryPolicy};
+    use manual_clock::ManualClock;
+    use std::sync::Mutex;
+
+    // 记录每次启动的启动项ID和时钟读数
+    type Launches = Arc<Mutex<Vec<(String, Duration)>>>;
+
+    fn item(id: &str, delay_seconds: u32) -> StartupItem {
+        StartupItem {
+            id: id.to_string(),
+            name: id.to_string(),
+            delay_enabled: delay_seconds > 0,
+            delay_seconds,
+            ..StartupItem::default()
+        }
+    }
+
+    fn launched(pid: u32) -> Launched {
+        Launched { pid: Some(pid), process: None, exit: None }
+    }
+
+    // 调度所有启动项，每次在任务都停下等待时钟后推进时钟，直到调度结束
+    async fn run_with<L>(clock: Arc<ManualClock>, items: Vec<StartupItem>, launch: L) -> (Vec<Outcome>, Vec<Progress>)
+    where
+        L: Fn(&StartupItem) -> Result<Launched, LaunchError> + Send + Sync + 'static,
+    {
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let recorded = progress.clone();
+        let handle = tokio::spawn(run(clock.clone(), items, launch, move |event| {
+            recorded.lock().unwrap().push(event);
+        }));
+
+        for _ in 0..1000 {
+            // 留出真实时间让启动函数在阻塞线程池中执行完
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            if handle.is_finished() {
+                let outcomes = handle.await.unwrap();
+                let progress = progress.lock().unwrap().clone();
+                return (outcomes, progress);
+            }
+            clock.advance_to_next();
+        }
+        panic!("scheduler did not finish");
+    }
+
+    // 启动时记录时钟读数，总是成功
+    fn recording_launch(clock: &Arc<ManualClock>, launches: &Launches) -> impl Fn(&StartupItem) -> Result<Launched, LaunchError> + Send + Sync + 'static {
+        let clock = clock.clone();
+        let launches = launches.clone();
+        move |item: &StartupItem| {
+            let mut launches = launches.lock().unwrap();
+            launches.push((item.id.clone(), clock.elapsed()));
+            Ok(launched(launches.len() as u32))
+        }
+    }
+
+    fn started_at(launches: &Launches, id: &str) -> Vec<Duration> {
+        launches.lock().unwrap().iter().filter(|(item, _)| item == id).map(|(_, at)| *at).collect()
+    }
+
+    #[tokio::test]
+    async fn delays_run_concurrently() {
+        let clock = ManualClock::new();
+        let launches = Launches::default();
+        let items = vec![item("a", 10), item("b", 10), item("c", 10)];
+
+        let (outcomes, _) = run_with(clock.clone(), items, recording_launch(&clock, &launches)).await;
+
+        for id in ["a", "b", "c"] {
+            assert_eq!(started_at(&launches, id), vec![Duration::from_secs(10)], "item {}", id);
+        }
+        assert!(outcomes.iter().all(|outcome| matches!(outcome.status, Status::Succeeded)));
+        assert!(outcomes.iter().all(|outcome| outcome.started == Some(Duration::from_secs(10))));
+        assert_eq!(clock.elapsed(), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn outcomes_keep_input_order() {
+        let clock = ManualClock::new();
+        let launches = Launches::default();
+        let items = vec![item("slow", 30), item("fast", 0), item("middle", 10)];
+
+        let (outcomes, _) = run_with(clock.clone(), items, recording_launch(&clock, &launches)).await;
+
+        let ids: Vec<&str> = outcomes.iter().map(|outcome| outcome.id.as_str()).collect();
+        assert_eq!(ids, ["slow", "fast", "middle"]);
+        let order: Vec<String> = launches.lock().unwrap().iter().map(|(id, _)| id.clone()).collect();
+        assert_eq!(order, ["fast", "middle", "slow"]);
+    }
+
+    #[tokio::test]
+    async fn dependents_wait_for_prerequisites() {
+        let clock = ManualClock::new();
+        let launches = Launches::default();
+        let mut b = item("b", 0);
+        b.depends_on = vec!["a".to_string()];
+        let mut c = item("c", 0);
+        c.depends_on = vec!["b".to_string()];
+        // 自身延迟晚于前置启动项时，按自身延迟启动
+        let mut d = item("d", 20);
+        d.depends_on = vec!["a".to_string()];
+        let items = vec![c, d, b, item("a", 10)];
+
+        let (outcomes, _) = run_with(clock.clone(), items, recording_launch(&clock, &launches)).await;
+
+        let order: Vec<String> = launches.lock().unwrap().iter().map(|(id, _)| id.clone()).collect();
+        assert_eq!(order, ["a", "b", "c", "d"]);
+        assert_eq!(started_at(&launches, "b"), vec![Duration::from_secs(10)]);
+        assert_eq!(started_at(&launches, "c"), vec![Duration::from_secs(10)]);
+        assert_eq!(started_at(&launches, "d"), vec![Duration::from_secs(20)]);
+        assert!(outcomes.iter().all(|outcome| matches!(outcome.status, Status::Succeeded)));
+    }
+
+    #[tokio::test]
+    async fn failed_prerequisite_skips_dependents() {
+        let clock = ManualClock::new();
+        let launches = Launches::default();
+        let mut b = item("b", 0);
+        b.depends_on = vec!["a".to_string()];
+        let items = vec![item("a", 5), b];
+
+        let record = recording_launch(&clock, &launches);
+        let (outcomes, _) = run_with(clock.clone(), items, move |item: &StartupItem| {
+            record(item)?;
+            Err(LaunchError::Spawn("not found".to_string()))
+        })
+        .await;
+
+        assert!(matches!(outcomes[0].status, Status::Failed(LaunchError::Spawn(_))));
+        assert!(matches!(&outcomes[1].status, Status::Skipped(SkipReason::Dependency { id, .. }) if id == "a"));
+        assert_eq!(started_at(&launches, "b"), Vec::<Duration>::new());
+    }
+
+    #[tokio::test]
+    async fn cycles_are_skipped() {
+        let clock = ManualClock::new();
+        let launches = Launches::default();
+        let mut a = item("a", 0);
+        a.depends_on = vec!["b".to_string()];
+        let mut b = item("b", 0);
+        b.depends_on = vec!["a".to_string()];
+        let mut c = item("c", 0);
+        c.depends_on = vec!["a".to_string()];
+        let items = vec![a, b, c, item("d", 0)];
+
+        assert_eq!(find_cycle(&items), Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]));
+        let (outcomes, _) = run_with(clock.clone(), items, recording_launch(&clock, &launches)).await;
+
+        for outcome in &outcomes[..3] {
+            assert!(matches!(outcome.status, Status::Skipped(SkipReason::Cycle)), "item {}", outcome.id);
+        }
+        assert!(matches!(outcomes[3].status, Status::Succeeded));
+        assert_eq!(launches.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn prerequisites_outside_the_run_are_unmet() {
+        let clock = ManualClock::new();
+        let launches = Launches::default();
+        // disabled不在本次运行的启动项中，例如已被禁用
+        let mut b = item("b", 0);
+        b.depends_on = vec!["a".to_string(), "disabled".to_string()];
+        let mut c = item("c", 0);
+        c.depends_on = vec!["b".to_string()];
+        let items = vec![item("a", 0), b, c];
+
+        let (outcomes, _) = run_with(clock.clone(), items, recording_launch(&clock, &launches)).await;
+
+        assert!(matches!(outcomes[0].status, Status::Succeeded));
+        assert!(matches!(&outcomes[1].status, Status::Skipped(SkipReason::Dependency { id, .. }) if id == "disabled"));
+        assert!(matches!(&outcomes[2].status, Status::Skipped(SkipReason::Dependency { id, .. }) if id == "b"));
+        assert_eq!(launches.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_follow_backoff() {
+        let clock = ManualClock::new();
+        let launches = Launches::default();
+        let mut flaky = item("flaky", 10);
+        flaky.retry = RetryPolicy {
+            max_attempts: 4,
+            backoff: Backoff::Exponential,
+            delay_seconds: 2,
+            max_delay_seconds: 60,
+            ..RetryPolicy::default()
+        };
+
+        // 前两次无法启动，第三次成功
+        let record = recording_launch(&clock, &launches);
+        let attempts = launches.clone();
+        let (outcomes, progress) = run_with(clock.clone(), vec![flaky], move |item: &StartupItem| {
+            let launched = record(item)?;
+            if attempts.lock().unwrap().len() < 3 {
+                return Err(LaunchError::Spawn("not ready".to_string()));
+            }
+            Ok(launched)
+        })
+        .await;
+
+        let secs = Duration::from_secs;
+        assert_eq!(started_at(&launches, "flaky"), vec![secs(10), secs(12), secs(16)]);
+        let retry_delays: Vec<Duration> = progress
+            .iter()
+            .filter_map(|event| match event {
+                Progress::Retrying { delay, .. } => Some(*delay),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(retry_delays, vec![secs(2), secs(4)]);
+        assert_eq!(outcomes[0].attempts, 3);
+        assert!(matches!(outcomes[0].status, Status::Succeeded));
+        assert_eq!(outcomes[0].started, Some(secs(10)));
+    }
+
+    #[tokio::test]
+    async fn exit_failures_are_not_retried_by_default() {
+        let clock = ManualClock::new();
+        let launches = Launches::default();
+        let mut item = item("exits", 0);
+        item.retry.max_attempts = 3;
+
+        let record = recording_launch(&clock, &launches);
+        let (outcomes, _) = run_with(clock.clone(), vec![item], move |item: &StartupItem| {
+            record(item)?;
+            Err(LaunchError::Exit { message: "exit 1".to_string(), code: Some(1), exited: true })
+        })
+        .await;
+
+        assert_eq!(outcomes[0].attempts, 1);
+        assert!(outcomes[0].spawned);
+        assert_eq!(outcomes[0].exit_code, Some(1));
+    }
+}
@@ -0,0 +1,67 @@
+// 只在测试调用advance时前进的时钟，用于测试依赖延迟和等待的逻辑
+
+use super::Clock;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+pub struct ManualClock {
+    start: Instant,
+    state: Mutex<ClockState>,
+}
+
+#[derive(Default)]
+struct ClockState {
+    elapsed: Duration,
+    sleepers: Vec<(Instant, oneshot::Sender<()>)>,
+}
+
+impl ManualClock {
+    pub fn new() -> Arc<ManualClock> {
+        Arc::new(ManualClock { start: Instant::now(), state: Mutex::new(ClockState::default()) })
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.state.lock().unwrap().elapsed
+    }
+
+    // 前进到最早的等待时间并唤醒所有到期的等待者，没有等待者时返回false
+    pub fn advance_to_next(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.sleepers.retain(|(_, tx)| !tx.is_closed());
+        let Some(next) = state.sleepers.iter().map(|(deadline, _)| *deadline).min() else {
+            return false;
+        };
+        state.elapsed = state.elapsed.max(next - self.start);
+
+        let now = self.start + state.elapsed;
+        let (due, pending) = std::mem::take(&mut state.sleepers)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(deadline, _)| *deadline <= now);
+        state.sleepers = pending;
+        for (_, tx) in due {
+            let _ = tx.send(());
+        }
+        true
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.state.lock().unwrap().elapsed
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let mut state = self.state.lock().unwrap();
+        if deadline <= self.start + state.elapsed {
+            return Box::pin(std::future::ready(()));
+        }
+        let (tx, rx) = oneshot::channel();
+        state.sleepers.push((deadline, tx));
+        Box::pin(async move {
+            let _ = rx.await;
+        })
+    }
+}
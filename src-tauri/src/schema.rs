@@ -0,0 +1,326 @@
+// 数据文件的版本和迁移
+//
+// 文件格式为 {"version": N, "<key>": 数据}。版本0是加入版本号之前的格式：
+// 启动项文件直接是数组，设置文件直接是设置对象。加载时依次执行迁移升级到当前版本，
+// 升级后的内容在下次保存时才写回文件。
+
+use crate::i18n::{trf, Message};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt;
+
+// 把版本i的文件内容升级为版本i+1
+type Migration = fn(Value) -> Result<Value, String>;
+
+pub struct Schema {
+    // 数据所在的字段名
+    key: &'static str,
+    // 下标i处的迁移把版本i升级为版本i+1，当前版本即迁移的数量
+    migrations: &'static [Migration],
+}
+
+// startup_items.json
+pub const STARTUP_ITEMS: Schema = Schema {
+    key: "items",
+    migrations: &[items_v0_to_v1],
+};
+
+// app_settings.json
+pub const APP_SETTINGS: Schema = Schema {
+    key: "settings",
+    migrations: &[settings_v0_to_v1],
+};
+
+#[derive(Debug)]
+pub enum SchemaError {
+    // 不是合法的JSON，或升级后的数据与当前结构不符
+    Json(serde_json::Error),
+    // version字段不是非负整数
+    InvalidVersion(Value),
+    // 文件由更新版本的EasiStartup写入
+    Newer { version: u32, supported: u32 },
+    // 迁移失败
+    Migration { from: u32, message: String },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Json(e) => write!(f, "{}", e),
+            SchemaError::InvalidVersion(value) => f.write_str(&trf(Message::InvalidSchemaVersion, &[value])),
+            SchemaError::Newer { version, supported } => {
+                f.write_str(&trf(Message::NewerSchemaVersion, &[version, supported]))
+            }
+            SchemaError::Migration { from, message } => {
+                f.write_str(&trf(Message::SchemaMigrationFailed, &[from, &(from + 1), message]))
+            }
+        }
+    }
+}
+
+impl SchemaError {
+    // 内容无法解析，说明文件已损坏；版本号不受支持时文件本身可能并无问题
+    pub fn is_corrupt(&self) -> bool {
+        matches!(self, SchemaError::Json(_))
+    }
+}
+
+impl From<serde_json::Error> for SchemaError {
+    fn from(error: serde_json::Error) -> Self {
+        SchemaError::Json(error)
+    }
+}
+
+// 加载的数据，以及文件原本的版本
+#[derive(Debug)]
+pub struct Loaded<T> {
+    pub data: T,
+    pub version: u32,
+}
+
+impl Schema {
+    pub fn version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    // 解析任意版本的文件内容
+    pub fn load<T: DeserializeOwned>(&self, content: &str) -> Result<Loaded<T>, SchemaError> {
+        let value: Value = serde_json::from_str(content)?;
+        let (mut value, version) = self.upgrade(value)?;
+
+        let data = value
+            .as_object_mut()
+            .and_then(|object| object.remove(self.key))
+            .unwrap_or(Value::Null);
+        Ok(Loaded { data: serde_json::from_value(data)?, version })
+    }
+
+    // 以当前版本的格式序列化
+    pub fn save<T: Serialize>(&self, data: &T) -> Result<String, SchemaError> {
+        let value = envelope(self.key, self.version(), serde_json::to_value(data)?);
+        Ok(serde_json::to_string_pretty(&value)?)
+    }
+
+    // 依次执行迁移，返回当前版本的内容和原本的版本
+    fn upgrade(&self, mut value: Value) -> Result<(Value, u32), SchemaError> {
+        let original = version_of(&value)?;
+        if original > self.version() {
+            return Err(SchemaError::Newer { version: original, supported: self.version() });
+        }
+
+        for (from, migration) in self.migrations.iter().enumerate().skip(original as usize) {
+            value = migration(value).map_err(|message| SchemaError::Migration { from: from as u32, message })?;
+        }
+        Ok((value, original))
+    }
+}
+
+// 读取文件的版本，没有version字段的对象和数组都视为版本0
+fn version_of(value: &Value) -> Result<u32, SchemaError> {
+    match value.get("version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| SchemaError::InvalidVersion(version.clone())),
+    }
+}
+
+// 按当前格式包装数据
+fn envelope(key: &str, version: u32, data: Value) -> Value {
+    let mut object = Map::new();
+    object.insert("version".to_string(), Value::from(version));
+    object.insert(key.to_string(), data);
+    Value::Object(object)
+}
+
+// 版本0的启动项文件是启动项数组；加入命令模式之前的启动项没有mode字段，都是直接启动程序
+fn items_v0_to_v1(value: Value) -> Result<Value, String> {
+    match value {
+        Value::Array(mut items) => {
+            for item in items.iter_mut().filter_map(Value::as_object_mut) {
+                item.entry("mode").or_insert_with(|| Value::from("normal"));
+            }
+            Ok(envelope("items", 1, Value::Array(items)))
+        }
+        other => Err(format!("expected an array of startup items, found {}", kind(&other))),
+    }
+}
+
+// 版本0的设置文件是设置对象
+fn settings_v0_to_v1(value: Value) -> Result<Value, String> {
+    match value {
+        Value::Object(settings) => Ok(envelope("settings", 1, Value::Object(settings))),
+        other => Err(format!("expected a settings object, found {}", kind(&other))),
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use crate::shell::Shell;
+    use crate::supervisor::RestartPolicy;
+    use crate::{migrate_item_arguments, AppSettings, Launch, StartupItem};
+
+    const ITEMS_V0: &str = include_str!("../tests/fixtures/schema/items_v0.json");
+    const ITEMS_V1: &str = include_str!("../tests/fixtures/schema/items_v1.json");
+    const SETTINGS_V0: &str = include_str!("../tests/fixtures/schema/settings_v0.json");
+    const SETTINGS_V1: &str = include_str!("../tests/fixtures/schema/settings_v1.json");
+
+    // 与读取启动项文件时相同，加载后迁移参数字符串
+    fn load_items(content: &str) -> Result<Loaded<Vec<StartupItem>>, SchemaError> {
+        let mut loaded: Loaded<Vec<StartupItem>> = STARTUP_ITEMS.load(content)?;
+        loaded.data.iter_mut().for_each(migrate_item_arguments);
+        Ok(loaded)
+    }
+
+    #[test]
+    fn items_v0_array_is_migrated() {
+        let loaded = load_items(ITEMS_V0).unwrap();
+        assert_eq!(loaded.version, 0);
+        assert_eq!(loaded.data.len(), 3);
+
+        let notepad = &loaded.data[0];
+        assert_eq!(notepad.id, "5f0c2a8e-1d7b-4c39-9a51-0e6f3b2d8c41");
+        assert_eq!(notepad.name, "记事本");
+        assert!(matches!(
+            &notepad.launch,
+            Launch::Executable { path, arguments, args: None } if path == "C:\\Windows\\notepad.exe" && arguments.is_empty()
+        ));
+        assert!(notepad.enabled);
+        assert!(notepad.delay_enabled);
+        assert_eq!(notepad.delay_seconds, 10);
+        // 版本0中还没有的字段使用默认值
+        assert!(notepad.environment.is_empty());
+        assert!(!notepad.wait_for_exit);
+        assert_eq!(notepad.restart, RestartPolicy::default());
+        assert!(notepad.depends_on.is_empty());
+        assert_eq!(notepad.dependency_timeout_seconds, 60);
+
+        let sync = &loaded.data[1];
+        assert_eq!(sync.name, "同步");
+        assert!(!sync.enabled);
+        assert!(matches!(
+            &sync.launch,
+            Launch::Shell { command, shell } if command == "robocopy C:\\data D:\\backup /mir" && *shell == Shell::default()
+        ));
+    }
+
+    #[test]
+    fn items_without_mode_are_executables() {
+        let loaded = load_items(ITEMS_V0).unwrap();
+        let tool = &loaded.data[2];
+
+        assert_eq!(tool.name, "旧版启动项");
+        assert!(tool.run_as_admin);
+        assert_eq!(tool.display_name, "");
+        assert!(!tool.delay_enabled);
+        assert_eq!(tool.delay_seconds, 5);
+        match &tool.launch {
+            Launch::Executable { path, arguments, args } => {
+                assert_eq!(path, "C:\\Program Files\\Tools\\tool.exe");
+                assert_eq!(arguments, "--minimized \"C:\\My Docs\"");
+                assert_eq!(args.as_deref(), Some(&["--minimized".to_string(), "C:\\My Docs".to_string()][..]));
+            }
+            other => panic!("unexpected launch {:?}", other),
+        }
+    }
+
+    #[test]
+    fn items_v1_envelope_loads_unchanged() {
+        let loaded = load_items(ITEMS_V1).unwrap();
+        assert_eq!(loaded.version, 1);
+        assert_eq!(
+            serde_json::to_value(&loaded.data).unwrap(),
+            serde_json::to_value(&load_items(ITEMS_V0).unwrap().data).unwrap()
+        );
+    }
+
+    #[test]
+    fn items_v1_require_a_mode() {
+        let content = r#"{"version": 1, "items": [{"id": "a", "executablePath": "app.exe"}]}"#;
+        assert!(matches!(load_items(content), Err(SchemaError::Json(_))));
+    }
+
+    #[test]
+    fn settings_v0_object_is_migrated() {
+        let loaded: Loaded<AppSettings> = APP_SETTINGS.load(SETTINGS_V0).unwrap();
+        assert_eq!(loaded.version, 0);
+
+        let settings = loaded.data;
+        assert!(settings.auto_startup_enabled);
+        assert!(!settings.auto_startup_as_admin);
+        assert!(settings.minimize_to_tray);
+        assert!(!settings.start_minimized);
+        assert!(settings.check_updates);
+        assert!(!settings.exit_after_startup);
+        assert_eq!(settings.language, None);
+    }
+
+    #[test]
+    fn settings_v1_envelope_loads_unchanged() {
+        let loaded: Loaded<AppSettings> = APP_SETTINGS.load(SETTINGS_V1).unwrap();
+        assert_eq!(loaded.version, 1);
+        assert!(loaded.data.auto_startup_enabled);
+        assert!(loaded.data.minimize_to_tray);
+        assert_eq!(loaded.data.language, Some(Language::EnUs));
+    }
+
+    #[test]
+    fn saved_content_is_current_version() {
+        let items: Vec<Value> = STARTUP_ITEMS.load(ITEMS_V0).unwrap().data;
+        let saved = STARTUP_ITEMS.save(&items).unwrap();
+
+        let value: Value = serde_json::from_str(&saved).unwrap();
+        assert_eq!(value["version"], STARTUP_ITEMS.version());
+        let reloaded: Loaded<Vec<Value>> = STARTUP_ITEMS.load(&saved).unwrap();
+        assert_eq!(reloaded.version, STARTUP_ITEMS.version());
+        assert_eq!(reloaded.data, items);
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let error = load_items(r#"{"version": 2, "items": []}"#).unwrap_err();
+        assert!(matches!(error, SchemaError::Newer { version: 2, supported: 1 }), "{:?}", error);
+        assert!(!error.is_corrupt());
+    }
+
+    #[test]
+    fn invalid_version_is_rejected() {
+        for version in ["-1", "1.5", "\"1\"", "null", "4294967296"] {
+            let content = format!(r#"{{"version": {}, "items": []}}"#, version);
+            let error = load_items(&content).unwrap_err();
+            assert!(matches!(error, SchemaError::InvalidVersion(_)), "{}: {:?}", version, error);
+        }
+    }
+
+    #[test]
+    fn wrong_shape_fails_migration() {
+        // 没有version字段的对象视为版本0，但版本0的启动项文件应是数组
+        let error = load_items(r#"{"items": []}"#).unwrap_err();
+        assert!(matches!(error, SchemaError::Migration { from: 0, .. }), "{:?}", error);
+
+        let error = APP_SETTINGS.load::<AppSettings>(ITEMS_V0).unwrap_err();
+        assert!(matches!(error, SchemaError::Migration { from: 0, .. }), "{:?}", error);
+    }
+
+    #[test]
+    fn invalid_json_is_a_json_error() {
+        assert!(load_items("[{").unwrap_err().is_corrupt());
+        // 升级后的数据与目标结构不符
+        assert!(matches!(load_items(r#"{"version": 1, "items": {}}"#), Err(SchemaError::Json(_))));
+    }
+}
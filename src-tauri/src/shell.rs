@@ -0,0 +1,311 @@
+// 命令模式使用的Shell及其命令行转义
+
+use crate::cmdline;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shell {
+    // cmd.exe
+    Cmd,
+    // Windows PowerShell (powershell.exe)
+    PowerShell,
+    // PowerShell 7 (pwsh)
+    Pwsh,
+    // POSIX sh
+    Sh,
+    Bash,
+}
+
+impl Default for Shell {
+    // 旧版配置没有shell字段，Windows上保持原先的PowerShell行为
+    fn default() -> Self {
+        if cfg!(windows) {
+            Shell::PowerShell
+        } else {
+            Shell::Sh
+        }
+    }
+}
+
+impl Shell {
+    // Shell的可执行文件名
+    pub fn program(self) -> &'static str {
+        match self {
+            Shell::Cmd => "cmd.exe",
+            Shell::PowerShell => "powershell.exe",
+            Shell::Pwsh => "pwsh",
+            Shell::Sh => "sh",
+            Shell::Bash => "bash",
+        }
+    }
+
+    // 执行脚本所需的参数列表
+    pub fn arguments(self, script: &str) -> Vec<String> {
+        match self {
+            // /s 会去掉首尾的引号并原样执行其余内容，命令中的引号无需转义
+            Shell::Cmd => vec![
+                "/d".to_string(),
+                "/s".to_string(),
+                "/c".to_string(),
+                format!("\"{}\"", script),
+            ],
+            // 使用Base64编码的UTF-16LE脚本，避免PowerShell自身的引号解析问题
+            Shell::PowerShell | Shell::Pwsh => vec![
+                "-ExecutionPolicy".to_string(),
+                "Bypass".to_string(),
+                "-EncodedCommand".to_string(),
+                encode_powershell_command(script),
+            ],
+            Shell::Sh | Shell::Bash => vec!["-c".to_string(), script.to_string()],
+        }
+    }
+
+    // 拼接后的参数字符串，用于ShellExecuteW等只接受单个命令行的场合
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn parameters(self, script: &str) -> String {
+        match self {
+            // cmd不遵循CommandLineToArgvW的转义规则，只能原样拼接
+            Shell::Cmd => self.arguments(script).join(" "),
+            _ => cmdline::join(&self.arguments(script)),
+        }
+    }
+
+    // 构建执行脚本的进程
+    pub fn command(self, script: &str) -> Command {
+        let mut cmd = Command::new(self.program());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.raw_arg(self.parameters(script));
+        }
+
+        #[cfg(not(windows))]
+        {
+            cmd.args(self.arguments(script));
+        }
+
+        cmd
+    }
+
+    // 生成在脚本开头切换工作目录和设置环境变量的语句
+    //
+    // 用于无法通过进程参数传递这些设置的场合，例如提权后由系统创建的进程。
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn prelude(
+        self,
+        working_dir: Option<&Path>,
+        environment: &BTreeMap<String, Option<String>>,
+    ) -> String {
+        let mut statements = Vec::new();
+
+        match self {
+            Shell::Cmd => {
+                if let Some(dir) = working_dir {
+                    statements.push(format!("cd /d \"{}\"", dir.display()));
+                }
+                for (name, value) in environment {
+                    statements.push(format!("set \"{}={}\"", name, value.as_deref().unwrap_or("")));
+                }
+            }
+            Shell::PowerShell | Shell::Pwsh => {
+                if let Some(dir) = working_dir {
+                    statements.push(format!(
+                        "Set-Location -LiteralPath {}",
+                        powershell_quote(&dir.to_string_lossy())
+                    ));
+                }
+                for (name, value) in environment {
+                    statements.push(match value {
+                        Some(value) => format!("${{env:{}}} = {}", name, powershell_quote(value)),
+                        None => format!(
+                            "Remove-Item -LiteralPath {} -ErrorAction SilentlyContinue",
+                            powershell_quote(&format!("Env:{}", name))
+                        ),
+                    });
+                }
+            }
+            Shell::Sh | Shell::Bash => {
+                if let Some(dir) = working_dir {
+                    statements.push(format!("cd {}", posix_quote(&dir.to_string_lossy())));
+                }
+                for (name, value) in environment {
+                    statements.push(match value {
+                        Some(value) => format!("export {}={}", name, posix_quote(value)),
+                        None => format!("unset {}", name),
+                    });
+                }
+            }
+        }
+
+        let separator = match self {
+            Shell::PowerShell | Shell::Pwsh => "; ",
+            Shell::Cmd | Shell::Sh | Shell::Bash => " && ",
+        };
+
+        statements
+            .into_iter()
+            .map(|statement| statement + separator)
+            .collect()
+    }
+}
+
+// PowerShell单引号字符串，内部的单引号需要写两次
+fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// POSIX单引号字符串，内部的单引号需要先结束引号再转义
+fn posix_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+// 生成 -EncodedCommand 所需的Base64(UTF-16LE)字符串
+fn encode_powershell_command(script: &str) -> String {
+    let bytes: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    base64_encode(&bytes)
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(TABLE[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn environment(values: &[(&str, Option<&str>)]) -> BTreeMap<String, Option<String>> {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.map(str::to_string)))
+            .collect()
+    }
+
+    #[test]
+    fn cmd_wraps_the_script_in_quotes() {
+        let script = r#"copy "C:\a b\x.txt" D:\ & echo done"#;
+        assert_eq!(
+            Shell::Cmd.arguments(script),
+            vec!["/d", "/s", "/c", r#""copy "C:\a b\x.txt" D:\ & echo done""#]
+        );
+        // cmd的参数原样拼接，不按CommandLineToArgvW的规则转义
+        assert_eq!(Shell::Cmd.parameters(script), r#"/d /s /c "copy "C:\a b\x.txt" D:\ & echo done""#);
+    }
+
+    #[test]
+    fn powershell_scripts_are_encoded() {
+        for shell in [Shell::PowerShell, Shell::Pwsh] {
+            assert_eq!(
+                shell.arguments("Get-Date"),
+                vec!["-ExecutionPolicy", "Bypass", "-EncodedCommand", "RwBlAHQALQBEAGEAdABlAA=="]
+            );
+            assert_eq!(
+                shell.parameters("Get-Date"),
+                "-ExecutionPolicy Bypass -EncodedCommand RwBlAHQALQBEAGEAdABlAA=="
+            );
+        }
+    }
+
+    #[test]
+    fn encoded_command_is_utf16le_base64() {
+        // UTF-16LE的长度分别为6、2、4字节，对应没有、两个和一个填充字符
+        assert_eq!(encode_powershell_command("dir"), "ZABpAHIA");
+        assert_eq!(encode_powershell_command("a"), "YQA=");
+        assert_eq!(encode_powershell_command("ab"), "YQBiAA==");
+        assert_eq!(encode_powershell_command(""), "");
+        // 非ASCII字符和代理对
+        assert_eq!(
+            encode_powershell_command("Write-Host '中文 😀'"),
+            "VwByAGkAdABlAC0ASABvAHMAdAAgACcALU6HZSAAPdgA3icA"
+        );
+    }
+
+    #[test]
+    fn base64_matches_rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (input, expected) in vectors {
+            assert_eq!(base64_encode(input.as_bytes()), expected, "{:?}", input);
+        }
+        assert_eq!(base64_encode(&[0xff, 0xfe, 0xfd]), "//79");
+    }
+
+    #[test]
+    fn posix_scripts_are_passed_with_c() {
+        assert_eq!(Shell::Sh.arguments("echo \"$HOME\""), vec!["-c", "echo \"$HOME\""]);
+        assert_eq!(Shell::Bash.program(), "bash");
+    }
+
+    #[test]
+    fn single_quotes_are_escaped() {
+        assert_eq!(powershell_quote("plain"), "'plain'");
+        assert_eq!(powershell_quote("it's 'quoted'"), "'it''s ''quoted'''");
+        assert_eq!(posix_quote("plain $HOME"), "'plain $HOME'");
+        assert_eq!(posix_quote("it's"), r"'it'\''s'");
+        assert_eq!(posix_quote(""), "''");
+    }
+
+    #[test]
+    fn empty_prelude_adds_nothing() {
+        for shell in [Shell::Cmd, Shell::PowerShell, Shell::Pwsh, Shell::Sh, Shell::Bash] {
+            assert_eq!(shell.prelude(None, &BTreeMap::new()), "");
+        }
+    }
+
+    #[test]
+    fn cmd_prelude_changes_drive_and_sets_variables() {
+        let environment = environment(&[("APP_HOME", Some(r"C:\My App")), ("TEMP_FLAG", None)]);
+        assert_eq!(
+            Shell::Cmd.prelude(Some(Path::new(r"D:\work dir")), &environment),
+            r#"cd /d "D:\work dir" && set "APP_HOME=C:\My App" && set "TEMP_FLAG=" && "#
+        );
+    }
+
+    #[test]
+    fn powershell_prelude_quotes_values() {
+        let environment = environment(&[("GREETING", Some("it's me")), ("PROXY", None)]);
+        assert_eq!(
+            Shell::PowerShell.prelude(Some(Path::new(r"C:\O'Brien")), &environment),
+            "Set-Location -LiteralPath 'C:\\O''Brien'; ${env:GREETING} = 'it''s me'; \
+             Remove-Item -LiteralPath 'Env:PROXY' -ErrorAction SilentlyContinue; "
+        );
+    }
+
+    #[test]
+    fn posix_prelude_quotes_values() {
+        let environment = environment(&[("GREETING", Some("it's $HOME")), ("PROXY", None)]);
+        for shell in [Shell::Sh, Shell::Bash] {
+            assert_eq!(
+                shell.prelude(Some(Path::new("/home/o'neil/app")), &environment),
+                r"cd '/home/o'\''neil/app' && export GREETING='it'\''s $HOME' && unset PROXY && "
+            );
+        }
+    }
+}
@@ -0,0 +1,276 @@
+// 数据文件的安全读写
+//
+// 写入时先写临时文件并刷到磁盘再替换原文件，断电时原文件要么是旧内容要么是新内容；
+// 覆盖前把旧内容备份到备份目录，读取时若文件损坏则使用最新的有效备份。
+
+use chrono::Local;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// 每个文件最多保留的备份数量
+const MAX_BACKUPS: usize = 10;
+
+// 写入内容：写临时文件、刷盘后替换原文件
+pub fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut temp_name = file_name.to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = File::create(&temp_path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&temp_path, path)?;
+    sync_parent(path);
+    Ok(())
+}
+
+// 确保重命名也已落盘；Windows上无法打开目录，重命名由文件系统日志保证
+fn sync_parent(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Err(e) = File::open(parent).and_then(|dir| dir.sync_all()) {
+            log::debug!("Failed to sync directory {}: {}", parent.display(), e);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+// 备份目录，备份文件名为 <原文件名>-<时间>.<扩展名>
+pub struct Backups {
+    dir: PathBuf,
+}
+
+impl Backups {
+    pub fn new(dir: PathBuf) -> Backups {
+        Backups { dir }
+    }
+
+    // path的所有备份，从新到旧排列
+    pub fn list(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let (stem, extension) = split_name(path);
+        let prefix = format!("{}-", stem);
+
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut backups = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(&prefix) && name.ends_with(&extension) {
+                backups.push(entry.path());
+            }
+        }
+        // 文件名中的时间可以按字符串排序
+        backups.sort();
+        backups.reverse();
+        Ok(backups)
+    }
+
+    // 备份path当前的内容；与最新的备份相同时不重复备份，并删除超出数量的旧备份
+    fn backup(&self, path: &Path, content: &str) -> io::Result<()> {
+        let existing = self.list(path)?;
+        if let Some(newest) = existing.first() {
+            if fs::read_to_string(newest).is_ok_and(|newest| newest == content) {
+                return Ok(());
+            }
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        let (stem, extension) = split_name(path);
+        let backup_path = self
+            .dir
+            .join(format!("{}-{}{}", stem, Local::now().format("%Y%m%d-%H%M%S-%3f"), extension));
+        write_atomic(&backup_path, content.as_bytes())?;
+
+        for old in existing.iter().skip(MAX_BACKUPS - 1) {
+            if let Err(e) = fs::remove_file(old) {
+                log::warn!("Failed to remove old backup {}: {}", old.display(), e);
+            }
+        }
+        Ok(())
+    }
+}
+
+// 拆分文件名为主干和扩展名（含点）
+fn split_name(path: &Path) -> (String, String) {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (stem, extension)
+}
+
+// 备份旧内容后写入新内容；is_valid用于判断旧内容是否值得备份，已损坏的内容不备份
+pub fn save(path: &Path, content: &str, backups: &Backups, is_valid: impl Fn(&str) -> bool) -> io::Result<()> {
+    match fs::read_to_string(path) {
+        Ok(old) if old != content && is_valid(&old) => {
+            if let Err(e) = backups.backup(path, &old) {
+                // 备份失败不影响保存
+                log::warn!("Failed to back up {}: {}", path.display(), e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("Failed to read {} for backup: {}", path.display(), e),
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomic(path, content.as_bytes())
+}
+
+#[derive(Debug)]
+pub enum ReadError<E> {
+    Io(io::Error),
+    Parse(E),
+}
+
+// 读取到的数据；recovered不为空时表示原文件已损坏，数据来自备份
+pub struct Read<T> {
+    pub data: T,
+    pub recovered: Option<Recovered>,
+}
+
+// 从备份恢复的信息
+pub struct Recovered {
+    pub backup: PathBuf,
+    // 原文件无法读取的原因
+    pub error: String,
+}
+
+// 读取并解析文件，文件不存在时返回None
+//
+// 文件无法读取或解析时依次尝试从新到旧的备份，使用第一个有效的备份覆盖原文件，
+// 损坏的内容另存为 <原文件名>.corrupt 以便排查；没有有效备份时返回原文件的错误。
+// is_corrupt为false的解析错误（例如文件由更新版本写入）不说明文件损坏，直接返回而不使用备份。
+pub fn read_or_recover<T, E: fmt::Display>(
+    path: &Path,
+    backups: &Backups,
+    parse: impl Fn(&str) -> Result<T, E>,
+    is_corrupt: impl Fn(&E) -> bool,
+) -> Result<Option<Read<T>>, ReadError<E>> {
+    let error = match fs::read_to_string(path) {
+        Ok(content) => match parse(&content) {
+            Ok(data) => return Ok(Some(Read { data, recovered: None })),
+            Err(e) if !is_corrupt(&e) => return Err(ReadError::Parse(e)),
+            Err(e) => ReadError::Parse(e),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => ReadError::Io(e),
+    };
+    let reason = match &error {
+        ReadError::Io(e) => e.to_string(),
+        ReadError::Parse(e) => e.to_string(),
+    };
+    log::warn!("Failed to load {}: {}, trying backups", path.display(), reason);
+
+    for backup in backups.list(path).unwrap_or_default() {
+        let Ok(content) = fs::read_to_string(&backup) else {
+            continue;
+        };
+        let Ok(data) = parse(&content) else {
+            log::warn!("Backup {} is not valid either", backup.display());
+            continue;
+        };
+
+        log::warn!("Recovered {} from backup {}", path.display(), backup.display());
+        let mut corrupt_name = path.file_name().unwrap_or_default().to_os_string();
+        corrupt_name.push(".corrupt");
+        if let Err(e) = fs::copy(path, path.with_file_name(corrupt_name)) {
+            log::warn!("Failed to keep corrupt copy of {}: {}", path.display(), e);
+        }
+        if let Err(e) = write_atomic(path, content.as_bytes()) {
+            log::warn!("Failed to restore {} from backup: {}", path.display(), e);
+        }
+        return Ok(Some(Read { data, recovered: Some(Recovered { backup, error: reason }) }));
+    }
+
+    Err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试结束时删除的临时目录
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> TempDir {
+            let dir = std::env::temp_dir().join(format!("easistartup-storage-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // 内容为数字时有效，"newer"表示由更新版本写入
+    fn parse(content: &str) -> Result<u32, String> {
+        match content {
+            "newer" => Err("newer".to_string()),
+            _ => content.parse().map_err(|_| "corrupt".to_string()),
+        }
+    }
+
+    fn is_corrupt(error: &String) -> bool {
+        error != "newer"
+    }
+
+    fn setup(content: &str) -> (TempDir, PathBuf, Backups) {
+        let temp = TempDir::new();
+        let path = temp.0.join("items.json");
+        let backups = Backups::new(temp.0.join("backups"));
+        save(&path, "1", &backups, |_| true).unwrap();
+        save(&path, "2", &backups, |_| true).unwrap();
+        fs::write(&path, content).unwrap();
+        (temp, path, backups)
+    }
+
+    #[test]
+    fn missing_file_is_none() {
+        let temp = TempDir::new();
+        let backups = Backups::new(temp.0.join("backups"));
+        assert!(read_or_recover(&temp.0.join("items.json"), &backups, parse, is_corrupt).unwrap().is_none());
+    }
+
+    #[test]
+    fn corrupt_file_is_restored_from_newest_backup() {
+        let (_temp, path, backups) = setup("{");
+
+        let read = read_or_recover(&path, &backups, parse, is_corrupt).unwrap().unwrap();
+        assert_eq!(read.data, 1);
+        let recovered = read.recovered.unwrap();
+        assert_eq!(recovered.error, "corrupt");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1");
+        assert_eq!(fs::read_to_string(path.with_file_name("items.json.corrupt")).unwrap(), "{");
+    }
+
+    #[test]
+    fn errors_that_are_not_corruption_skip_backups() {
+        let (_temp, path, backups) = setup("newer");
+
+        let error = read_or_recover(&path, &backups, parse, is_corrupt).err().unwrap();
+        assert!(matches!(error, ReadError::Parse(e) if e == "newer"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "newer");
+        assert!(!path.with_file_name("items.json.corrupt").exists());
+    }
+}
@@ -0,0 +1,557 @@
+// 保活监控：持有已启动进程的句柄，进程退出后按重启策略重新启动
+
+use crate::i18n::{trf, Message};
+use crate::process::ExitInfo;
+use crate::retry::Backoff;
+use crate::scheduler::{Clock, LaunchError, Launched};
+use crate::StartupItem;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+// 什么情况下重启
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RestartMode {
+    // 不监控，启动后即不再关心
+    #[default]
+    Never,
+    // 退出码非0时重启
+    OnFailure,
+    // 无论如何退出都重启
+    Always,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    // window_seconds内最多重启的次数，超过后放弃
+    pub max_restarts: u32,
+    pub window_seconds: u32,
+    pub backoff: Backoff,
+    // 第一次重启前等待的秒数
+    pub delay_seconds: u32,
+    // 指数退避时单次等待的上限
+    pub max_delay_seconds: u32,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            mode: RestartMode::Never,
+            max_restarts: 5,
+            window_seconds: 300,
+            backoff: Backoff::Exponential,
+            delay_seconds: 1,
+            max_delay_seconds: 60,
+        }
+    }
+}
+
+impl RestartPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.mode != RestartMode::Never
+    }
+
+    // 进程以exit退出后是否需要重启
+    fn should_restart(&self, exit: &ExitInfo) -> bool {
+        match self.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure => !exit.success,
+            RestartMode::Always => true,
+        }
+    }
+}
+
+// 被监控启动项的当前状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum SupervisedState {
+    // 正在运行
+    #[serde(rename_all = "camelCase")]
+    Running { pid: Option<u32> },
+    // 已退出，将在delay_seconds后重启
+    #[serde(rename_all = "camelCase")]
+    Restarting { exit_code: Option<i32>, delay_seconds: u64 },
+    // 已退出，按策略不需要重启
+    #[serde(rename_all = "camelCase")]
+    Exited { exit_code: Option<i32>, success: bool },
+    // 短时间内重启次数过多，已放弃
+    #[serde(rename_all = "camelCase")]
+    GaveUp { exit_code: Option<i32> },
+    // 已手动停止监控
+    Stopped,
+}
+
+// 发给界面的状态事件，也用于查询当前状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupervisedStatus {
+    pub id: String,
+    pub name: String,
+    // 累计重启次数
+    pub restarts: u32,
+    #[serde(flatten)]
+    pub state: SupervisedState,
+}
+
+impl SupervisedStatus {
+    fn is_active(&self) -> bool {
+        matches!(self.state, SupervisedState::Running { .. } | SupervisedState::Restarting { .. })
+    }
+}
+
+type LaunchFn = dyn Fn(&StartupItem) -> Result<Launched, LaunchError> + Send + Sync;
+type NotifyFn = dyn Fn(&SupervisedStatus) + Send + Sync;
+
+struct Entry {
+    generation: u64,
+    stop: watch::Sender<bool>,
+    status: SupervisedStatus,
+}
+
+struct Inner {
+    clock: Arc<dyn Clock>,
+    launch: Box<LaunchFn>,
+    notify: Box<NotifyFn>,
+    entries: Mutex<HashMap<String, Entry>>,
+    next_generation: AtomicU64,
+    // 正在监控的启动项数量
+    active: watch::Sender<usize>,
+}
+
+#[derive(Clone)]
+pub struct Supervisor {
+    inner: Arc<Inner>,
+}
+
+impl Supervisor {
+    // launch用于重新启动启动项，notify在状态变化时调用
+    pub fn new<L, N>(clock: Arc<dyn Clock>, launch: L, notify: N) -> Supervisor
+    where
+        L: Fn(&StartupItem) -> Result<Launched, LaunchError> + Send + Sync + 'static,
+        N: Fn(&SupervisedStatus) + Send + Sync + 'static,
+    {
+        Supervisor {
+            inner: Arc::new(Inner {
+                clock,
+                launch: Box::new(launch),
+                notify: Box::new(notify),
+                entries: Mutex::new(HashMap::new()),
+                next_generation: AtomicU64::new(0),
+                active: watch::channel(0).0,
+            }),
+        }
+    }
+
+    // 开始监控已启动的进程；同一启动项已在监控时，停止旧的监控
+    pub fn supervise(&self, item: StartupItem, launched: Launched) {
+        let generation = self.inner.next_generation.fetch_add(1, Ordering::Relaxed);
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let status = SupervisedStatus {
+            id: item.id.clone(),
+            name: item.name.clone(),
+            restarts: 0,
+            state: SupervisedState::Running { pid: launched.pid },
+        };
+
+        let previous = self.inner.entries.lock().unwrap().insert(
+            item.id.clone(),
+            Entry { generation, stop: stop_tx, status: status.clone() },
+        );
+        if let Some(previous) = previous {
+            let _ = previous.stop.send(true);
+        }
+
+        log::info!("Supervising startup item '{}' with restart mode {:?}", item.name, item.restart.mode);
+        (self.inner.notify)(&status);
+
+        self.inner.active.send_modify(|count| *count += 1);
+        let inner = self.inner.clone();
+        tauri::async_runtime::spawn(async move {
+            supervise_loop(&inner, generation, item, launched, stop_rx).await;
+            inner.active.send_modify(|count| *count -= 1);
+        });
+    }
+
+    // 停止监控启动项，不会结束正在运行的进程
+    pub fn stop(&self, id: &str) -> bool {
+        let entries = self.inner.entries.lock().unwrap();
+        match entries.get(id) {
+            Some(entry) if entry.status.is_active() => entry.stop.send(true).is_ok(),
+            _ => false,
+        }
+    }
+
+    // 所有被监控过的启动项的当前状态
+    pub fn statuses(&self) -> Vec<SupervisedStatus> {
+        let entries = self.inner.entries.lock().unwrap();
+        let mut statuses: Vec<_> = entries.values().map(|entry| entry.status.clone()).collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self.inner.active.borrow() > 0
+    }
+
+    // 等待所有启动项都不再需要监控
+    pub async fn wait_idle(&self) {
+        let mut rx = self.inner.active.subscribe();
+        let _ = rx.wait_for(|count| *count == 0).await;
+    }
+}
+
+// 更新状态并通知界面；已被新的监控取代时不再更新
+fn publish(inner: &Inner, generation: u64, id: &str, restarts: u32, state: SupervisedState) {
+    let status = {
+        let mut entries = inner.entries.lock().unwrap();
+        match entries.get_mut(id) {
+            Some(entry) if entry.generation == generation => {
+                entry.status.restarts = restarts;
+                entry.status.state = state;
+                entry.status.clone()
+            }
+            _ => return,
+        }
+    };
+    (inner.notify)(&status);
+}
+
+async fn supervise_loop(
+    inner: &Arc<Inner>,
+    generation: u64,
+    item: StartupItem,
+    mut launched: Launched,
+    mut stop: watch::Receiver<bool>,
+) {
+    let policy = item.restart;
+    let window = Duration::from_secs(policy.window_seconds as u64);
+    // 时间窗口内每次重启的时间
+    let mut recent: VecDeque<Instant> = VecDeque::new();
+    let mut restarts = 0;
+
+    loop {
+        let exit = match (launched.exit.take(), launched.process.take()) {
+            (Some(exit), _) => exit,
+            (None, Some(mut process)) => {
+                let wait = tokio::task::spawn_blocking(move || process.wait_timeout(None));
+                tokio::select! {
+                    result = wait => match result {
+                        // 不设超时的等待总会得到退出结果
+                        Ok(Ok(exit)) => exit.unwrap_or(ExitInfo { code: None, success: false }),
+                        Ok(Err(e)) => {
+                            log::warn!("Failed to wait for supervised startup item '{}': {}", item.name, e);
+                            ExitInfo { code: None, success: false }
+                        }
+                        Err(e) => {
+                            log::warn!("Supervisor wait task for '{}' failed: {}", item.name, e);
+                            ExitInfo { code: None, success: false }
+                        }
+                    },
+                    _ = stop.wait_for(|stopped| *stopped) => {
+                        log::info!("Stopped supervising startup item '{}'", item.name);
+                        publish(inner, generation, &item.id, restarts, SupervisedState::Stopped);
+                        return;
+                    }
+                }
+            }
+            (None, None) => {
+                // 以管理员身份启动等情况下可能拿不到进程句柄
+                log::warn!("Cannot supervise startup item '{}' without a process handle", item.name);
+                publish(inner, generation, &item.id, restarts, SupervisedState::Exited { exit_code: None, success: false });
+                return;
+            }
+        };
+
+        log::info!("Supervised startup item '{}' exited with code {:?}", item.name, exit.code);
+
+        if *stop.borrow() {
+            publish(inner, generation, &item.id, restarts, SupervisedState::Stopped);
+            return;
+        }
+
+        if !policy.should_restart(&exit) {
+            publish(inner, generation, &item.id, restarts, SupervisedState::Exited { exit_code: exit.code, success: exit.success });
+            return;
+        }
+
+        let now = inner.clock.now();
+        while recent.front().is_some_and(|at| now.saturating_duration_since(*at) >= window) {
+            recent.pop_front();
+        }
+        if recent.len() >= policy.max_restarts as usize {
+            log::warn!(
+                "Startup item '{}' restarted {} times within {} seconds, giving up",
+                item.name, recent.len(), policy.window_seconds
+            );
+            publish(inner, generation, &item.id, restarts, SupervisedState::GaveUp { exit_code: exit.code });
+            return;
+        }
+
+        let delay = policy.backoff.delay(policy.delay_seconds, policy.max_delay_seconds, recent.len() as u32 + 1);
+        publish(
+            inner,
+            generation,
+            &item.id,
+            restarts,
+            SupervisedState::Restarting { exit_code: exit.code, delay_seconds: delay.as_secs() },
+        );
+
+        tokio::select! {
+            _ = inner.clock.sleep_until(now + delay) => {}
+            _ = stop.wait_for(|stopped| *stopped) => {
+                publish(inner, generation, &item.id, restarts, SupervisedState::Stopped);
+                return;
+            }
+        }
+
+        recent.push_back(inner.clock.now());
+        restarts += 1;
+        log::info!("Restarting startup item '{}' (restart #{})", item.name, restarts);
+
+        let relaunch_inner = inner.clone();
+        let relaunch_item = item.clone();
+        let result = tokio::task::spawn_blocking(move || (relaunch_inner.launch)(&relaunch_item))
+            .await
+            .unwrap_or_else(|e| Err(LaunchError::Spawn(trf(Message::TaskAborted, &[&e]))));
+
+        launched = match result {
+            Ok(launched) => {
+                publish(inner, generation, &item.id, restarts, SupervisedState::Running { pid: launched.pid });
+                launched
+            }
+            Err(e) => {
+                // 重新启动失败也算一次失败退出，继续按策略重试
+                log::error!("Failed to restart startup item '{}': {}", item.name, e);
+                Launched { pid: None, process: None, exit: Some(ExitInfo { code: None, success: false }) }
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::manual_clock::ManualClock;
+
+    // 记录每次重新启动时的时钟读数
+    type Launches = Arc<Mutex<Vec<Duration>>>;
+    // 按顺序记录通知的状态
+    type States = Arc<Mutex<Vec<SupervisedState>>>;
+
+    fn item(mode: RestartMode, backoff: Backoff, delay_seconds: u32) -> StartupItem {
+        StartupItem {
+            id: "app".to_string(),
+            name: "app".to_string(),
+            restart: RestartPolicy {
+                mode,
+                max_restarts: 3,
+                window_seconds: 300,
+                backoff,
+                delay_seconds,
+                max_delay_seconds: 60,
+            },
+            ..StartupItem::default()
+        }
+    }
+
+    // 已经以code退出的进程
+    fn exited(code: i32) -> Launched {
+        Launched { pid: Some(1), process: None, exit: Some(ExitInfo { code: Some(code), success: code == 0 }) }
+    }
+
+    // 每次重新启动的进程都立即以code退出
+    fn supervisor(clock: &Arc<ManualClock>, code: i32) -> (Supervisor, Launches, States) {
+        let launches = Launches::default();
+        let states = States::default();
+        let (recorded_clock, recorded_launches, recorded_states) = (clock.clone(), launches.clone(), states.clone());
+        let supervisor = Supervisor::new(
+            clock.clone(),
+            move |_: &StartupItem| {
+                recorded_launches.lock().unwrap().push(recorded_clock.elapsed());
+                Ok(exited(code))
+            },
+            move |status: &SupervisedStatus| recorded_states.lock().unwrap().push(status.state.clone()),
+        );
+        (supervisor, launches, states)
+    }
+
+    // 每次在监控都停下等待时钟后推进时钟，直到没有需要监控的启动项
+    async fn run_until_idle(clock: &ManualClock, supervisor: &Supervisor) {
+        for _ in 0..1000 {
+            // 留出真实时间让启动函数在阻塞线程池中执行完
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            if !supervisor.is_active() {
+                return;
+            }
+            clock.advance_to_next();
+        }
+        panic!("supervisor did not become idle");
+    }
+
+    fn seconds(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|value| Duration::from_secs(*value)).collect()
+    }
+
+    fn status(supervisor: &Supervisor) -> SupervisedStatus {
+        let mut statuses = supervisor.statuses();
+        assert_eq!(statuses.len(), 1);
+        statuses.remove(0)
+    }
+
+    #[tokio::test]
+    async fn failed_exits_are_restarted_with_growing_delays_until_giving_up() {
+        let clock = ManualClock::new();
+        let (supervisor, launches, states) = supervisor(&clock, 1);
+
+        supervisor.supervise(item(RestartMode::OnFailure, Backoff::Exponential, 1), exited(1));
+        run_until_idle(&clock, &supervisor).await;
+
+        // 等待1、2、4秒后重启，第3次重启后仍然失败即放弃
+        assert_eq!(*launches.lock().unwrap(), seconds(&[1, 3, 7]));
+        let status = status(&supervisor);
+        assert_eq!(status.restarts, 3);
+        assert_eq!(status.state, SupervisedState::GaveUp { exit_code: Some(1) });
+
+        let restarting = |delay_seconds| SupervisedState::Restarting { exit_code: Some(1), delay_seconds };
+        let running = SupervisedState::Running { pid: Some(1) };
+        assert_eq!(
+            *states.lock().unwrap(),
+            vec![
+                running.clone(),
+                restarting(1),
+                running.clone(),
+                restarting(2),
+                running.clone(),
+                restarting(4),
+                running,
+                SupervisedState::GaveUp { exit_code: Some(1) },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fixed_backoff_waits_the_same_delay() {
+        let clock = ManualClock::new();
+        let (supervisor, launches, _) = supervisor(&clock, 1);
+
+        supervisor.supervise(item(RestartMode::Always, Backoff::Fixed, 5), exited(1));
+        run_until_idle(&clock, &supervisor).await;
+
+        assert_eq!(*launches.lock().unwrap(), seconds(&[5, 10, 15]));
+    }
+
+    #[tokio::test]
+    async fn failed_relaunches_count_as_failed_exits() {
+        let clock = ManualClock::new();
+        let launches = Launches::default();
+        let recorded = launches.clone();
+        let launch_clock = clock.clone();
+        let supervisor = Supervisor::new(
+            clock.clone(),
+            move |_: &StartupItem| {
+                recorded.lock().unwrap().push(launch_clock.elapsed());
+                Err(LaunchError::Spawn("missing".to_string()))
+            },
+            |_: &SupervisedStatus| {},
+        );
+
+        supervisor.supervise(item(RestartMode::OnFailure, Backoff::Fixed, 1), exited(1));
+        run_until_idle(&clock, &supervisor).await;
+
+        assert_eq!(*launches.lock().unwrap(), seconds(&[1, 2, 3]));
+        assert_eq!(status(&supervisor).state, SupervisedState::GaveUp { exit_code: None });
+    }
+
+    #[tokio::test]
+    async fn exits_not_covered_by_the_mode_are_not_restarted() {
+        let clock = ManualClock::new();
+        let (supervisor, launches, _) = supervisor(&clock, 0);
+
+        supervisor.supervise(item(RestartMode::OnFailure, Backoff::Fixed, 1), exited(0));
+        run_until_idle(&clock, &supervisor).await;
+
+        assert!(launches.lock().unwrap().is_empty());
+        assert_eq!(status(&supervisor).state, SupervisedState::Exited { exit_code: Some(0), success: true });
+    }
+
+    #[tokio::test]
+    async fn always_restarts_successful_exits() {
+        let clock = ManualClock::new();
+        let (supervisor, launches, _) = supervisor(&clock, 0);
+
+        supervisor.supervise(item(RestartMode::Always, Backoff::Fixed, 1), exited(0));
+        run_until_idle(&clock, &supervisor).await;
+
+        assert_eq!(launches.lock().unwrap().len(), 3);
+        assert_eq!(status(&supervisor).state, SupervisedState::GaveUp { exit_code: Some(0) });
+    }
+
+    #[tokio::test]
+    async fn processes_without_a_handle_cannot_be_supervised() {
+        let clock = ManualClock::new();
+        let (supervisor, launches, _) = supervisor(&clock, 1);
+
+        let launched = Launched { pid: Some(1), process: None, exit: None };
+        supervisor.supervise(item(RestartMode::Always, Backoff::Fixed, 1), launched);
+        run_until_idle(&clock, &supervisor).await;
+
+        assert!(launches.lock().unwrap().is_empty());
+        assert_eq!(status(&supervisor).state, SupervisedState::Exited { exit_code: None, success: false });
+    }
+
+    #[tokio::test]
+    async fn stop_cancels_a_pending_restart() {
+        let clock = ManualClock::new();
+        let (supervisor, launches, _) = supervisor(&clock, 1);
+
+        supervisor.supervise(item(RestartMode::OnFailure, Backoff::Fixed, 30), exited(1));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(status(&supervisor).state, SupervisedState::Restarting { exit_code: Some(1), delay_seconds: 30 });
+
+        assert!(supervisor.stop("app"));
+        run_until_idle(&clock, &supervisor).await;
+
+        assert!(launches.lock().unwrap().is_empty());
+        assert_eq!(status(&supervisor).state, SupervisedState::Stopped);
+        // 已停止或未监控的启动项无法再停止
+        assert!(!supervisor.stop("app"));
+        assert!(!supervisor.stop("other"));
+    }
+
+    #[tokio::test]
+    async fn newer_supervision_replaces_the_older_one() {
+        let clock = ManualClock::new();
+        let (supervisor, launches, states) = supervisor(&clock, 1);
+
+        supervisor.supervise(item(RestartMode::OnFailure, Backoff::Fixed, 30), exited(1));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        supervisor.supervise(item(RestartMode::OnFailure, Backoff::Fixed, 30), exited(0));
+        run_until_idle(&clock, &supervisor).await;
+
+        // 旧的监控停止时不再更新状态
+        assert!(launches.lock().unwrap().is_empty());
+        assert_eq!(status(&supervisor).state, SupervisedState::Exited { exit_code: Some(0), success: true });
+        assert!(!states.lock().unwrap().contains(&SupervisedState::Stopped));
+    }
+
+    #[tokio::test]
+    async fn wait_idle_returns_once_supervision_ends() {
+        let clock = ManualClock::new();
+        let (supervisor, _, _) = supervisor(&clock, 1);
+        supervisor.wait_idle().await;
+
+        supervisor.supervise(item(RestartMode::OnFailure, Backoff::Fixed, 30), exited(1));
+        assert!(supervisor.is_active());
+        assert!(tokio::time::timeout(Duration::from_millis(50), supervisor.wait_idle()).await.is_err());
+
+        supervisor.stop("app");
+        tokio::time::timeout(Duration::from_secs(5), supervisor.wait_idle()).await.unwrap();
+        assert!(!supervisor.is_active());
+    }
+}
@@ -0,0 +1,704 @@
+// 系统中已有的自启动项
+//
+// Windows：HKCU/HKLM 的 Run 和 RunOnce 注册表项、当前用户和所有用户的启动文件夹；
+// Linux：~/.config/autostart 中的 .desktop 文件。导入后可以禁用原来的自启动项，
+// 由EasiStartup统一管理。Windows上写入StartupApproved（与任务管理器中的“禁用”相同，
+// 可以在任务管理器中恢复），Linux上在 .desktop 文件中写入 Hidden=true。
+
+use crate::error::AppError;
+use crate::i18n::{trf, Message};
+use crate::{cmdline, Launch, StartupItem};
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// 普通自启动使用的注册表项
+#[cfg(windows)]
+pub const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+#[cfg(windows)]
+const RUN_ONCE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\RunOnce";
+// 任务管理器“启动”页中的启用状态
+#[cfg(windows)]
+const APPROVED_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\StartupApproved";
+
+// 各平台只会用到其中一部分
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+pub enum Location {
+    // HKCU\...\Run
+    UserRun,
+    // HKLM\...\Run
+    MachineRun,
+    // HKCU\...\RunOnce
+    UserRunOnce,
+    // HKLM\...\RunOnce
+    MachineRunOnce,
+    // 当前用户的启动文件夹
+    UserStartupFolder,
+    // 所有用户的启动文件夹
+    CommonStartupFolder,
+    // ~/.config/autostart
+    XdgAutostart,
+}
+
+impl Location {
+    fn key(self) -> &'static str {
+        match self {
+            Location::UserRun => "userRun",
+            Location::MachineRun => "machineRun",
+            Location::UserRunOnce => "userRunOnce",
+            Location::MachineRunOnce => "machineRunOnce",
+            Location::UserStartupFolder => "userStartupFolder",
+            Location::CommonStartupFolder => "commonStartupFolder",
+            Location::XdgAutostart => "xdgAutostart",
+        }
+    }
+
+    // RunOnce只运行一次，没有对应的禁用方式
+    fn can_disable(self) -> bool {
+        !matches!(self, Location::UserRunOnce | Location::MachineRunOnce)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemEntry {
+    // 位置和名称组成的标识，导入时用于指定自启动项
+    pub id: String,
+    pub name: String,
+    pub location: Location,
+    // 注册表值名，或快捷方式、.desktop文件的路径
+    pub source: String,
+    // 原始命令行，仅用于显示
+    pub command: String,
+    pub enabled: bool,
+    pub can_disable: bool,
+    #[serde(skip)]
+    launch: Launch,
+    #[serde(skip)]
+    working_directory: String,
+    #[serde(skip)]
+    icon: String,
+}
+
+impl SystemEntry {
+    // 转换为启动项，原来已禁用的自启动项导入后同样是禁用状态
+    pub fn to_startup_item(&self) -> StartupItem {
+        StartupItem {
+            name: self.name.clone(),
+            launch: self.launch.clone(),
+            icon: self.icon.clone(),
+            working_directory: self.working_directory.clone(),
+            // REG_EXPAND_SZ中常见 %ProgramFiles% 之类的引用
+            expand_environment: cfg!(windows) && self.command.contains('%'),
+            enabled: self.enabled,
+            ..StartupItem::default()
+        }
+    }
+
+    // 是否就是EasiStartup自身的自启动项
+    fn is_program(&self, exe: &Path) -> bool {
+        match &self.launch {
+            Launch::Executable { path, .. } => {
+                let path = Path::new(path);
+                if cfg!(windows) {
+                    path.to_string_lossy().eq_ignore_ascii_case(&exe.to_string_lossy())
+                } else {
+                    path == exe
+                }
+            }
+            Launch::Shell { .. } => false,
+        }
+    }
+}
+
+// 禁用失败的自启动项
+#[derive(Debug, Clone, Serialize)]
+pub struct DisableFailure {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemImportSummary {
+    pub imported: usize,
+    pub disabled: usize,
+    pub failures: Vec<DisableFailure>,
+}
+
+// 列出系统中的自启动项，不包括EasiStartup自身；无法读取的位置只记录日志
+pub fn list(exe: &Path) -> Vec<SystemEntry> {
+    let mut entries = Vec::new();
+
+    #[cfg(windows)]
+    {
+        for location in [Location::UserRun, Location::MachineRun, Location::UserRunOnce, Location::MachineRunOnce] {
+            if let Err(e) = list_run_key(location, &mut entries) {
+                log::debug!("Failed to read {:?} autostart entries: {}", location, e);
+            }
+        }
+        for location in [Location::UserStartupFolder, Location::CommonStartupFolder] {
+            if let Err(e) = list_startup_folder(location, &mut entries) {
+                log::debug!("Failed to read {:?} autostart entries: {}", location, e);
+            }
+        }
+    }
+
+    #[cfg(not(windows))]
+    if let Err(e) = list_xdg_autostart(&mut entries) {
+        log::debug!("Failed to read XDG autostart entries: {}", e);
+    }
+
+    entries.retain(|entry| !entry.is_program(exe));
+    entries
+}
+
+// 禁用自启动项
+pub fn disable(entry: &SystemEntry) -> Result<(), AppError> {
+    if !entry.can_disable {
+        return Err(AppError::Unsupported(trf(Message::SystemEntryCannotDisable, &[&entry.name])));
+    }
+
+    match entry.location {
+        #[cfg(not(windows))]
+        Location::XdgAutostart => {
+            let path = Path::new(&entry.source);
+            let content = fs::read_to_string(path)
+                .map_err(|e| AppError::io(Message::DisableSystemEntry, e))?;
+            crate::storage::write_atomic(path, set_hidden(&content).as_bytes())
+                .map_err(|e| AppError::io(Message::DisableSystemEntry, e))
+        }
+        #[cfg(windows)]
+        location => {
+            // 启动文件夹中的项目以文件名记录
+            let name = match location {
+                Location::UserStartupFolder | Location::CommonStartupFolder => Path::new(&entry.source)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                _ => entry.source.clone(),
+            };
+            set_disabled(location, &name).map_err(|e| AppError::registry(Message::DisableSystemEntry, e))
+        }
+        #[cfg(not(windows))]
+        _ => Err(AppError::Unsupported(trf(Message::SystemEntryCannotDisable, &[&entry.name]))),
+    }
+}
+
+// 拆分注册表Run值中的命令行，未加引号的程序路径中可能包含空格
+#[cfg(windows)]
+fn split_command(command: &str) -> (String, Vec<String>) {
+    let command = command.trim();
+    let (program, rest) = match command.strip_prefix('"') {
+        Some(quoted) => match quoted.find('"') {
+            Some(end) => (&quoted[..end], &quoted[end + 1..]),
+            None => (quoted, ""),
+        },
+        None => {
+            let end = command
+                .to_ascii_lowercase()
+                .find(".exe")
+                .map(|index| index + ".exe".len())
+                .filter(|&end| command[end..].is_empty() || command[end..].starts_with([' ', '\t']))
+                .or_else(|| command.find([' ', '\t']))
+                .unwrap_or(command.len());
+            command.split_at(end)
+        }
+    };
+    (program.to_string(), cmdline::split(rest))
+}
+
+fn executable(path: String, args: Vec<String>) -> Launch {
+    Launch::Executable { path, arguments: cmdline::join(&args), args: Some(args) }
+}
+
+#[cfg(windows)]
+fn hive(location: Location) -> winreg::RegKey {
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    match location {
+        Location::UserRun | Location::UserRunOnce | Location::UserStartupFolder => {
+            winreg::RegKey::predef(HKEY_CURRENT_USER)
+        }
+        _ => winreg::RegKey::predef(HKEY_LOCAL_MACHINE),
+    }
+}
+
+// StartupApproved下对应的子项，RunOnce没有启用状态
+#[cfg(windows)]
+fn approved_key(location: Location) -> Option<String> {
+    let subkey = match location {
+        Location::UserRun | Location::MachineRun => "Run",
+        Location::UserStartupFolder | Location::CommonStartupFolder => "StartupFolder",
+        _ => return None,
+    };
+    Some(format!("{}\\{}", APPROVED_KEY, subkey))
+}
+
+// 值的第一个字节为奇数表示已禁用，没有记录时为启用
+#[cfg(windows)]
+fn is_approved(location: Location, name: &str) -> bool {
+    let Some(key) = approved_key(location) else {
+        return true;
+    };
+    hive(location)
+        .open_subkey(key)
+        .and_then(|key| key.get_raw_value(name))
+        .map_or(true, |value| value.bytes.first().is_none_or(|flag| flag & 1 == 0))
+}
+
+// 写入禁用状态：4字节标记和8字节的禁用时间（FILETIME）
+#[cfg(windows)]
+fn set_disabled(location: Location, name: &str) -> io::Result<()> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use winreg::enums::REG_BINARY;
+
+    let Some(key) = approved_key(location) else {
+        return Err(io::Error::from(io::ErrorKind::Unsupported));
+    };
+    // FILETIME从1601年开始，以100纳秒为单位
+    let since_unix = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let filetime = (since_unix.as_nanos() / 100) as u64 + 116_444_736_000_000_000;
+
+    let mut bytes = vec![0x03, 0, 0, 0];
+    bytes.extend_from_slice(&filetime.to_le_bytes());
+    let (key, _) = hive(location).create_subkey(key)?;
+    key.set_raw_value(name, &winreg::RegValue { bytes: bytes.into(), vtype: REG_BINARY })
+}
+
+#[cfg(windows)]
+fn list_run_key(location: Location, entries: &mut Vec<SystemEntry>) -> io::Result<()> {
+    let subkey = match location {
+        Location::UserRun | Location::MachineRun => RUN_KEY,
+        _ => RUN_ONCE_KEY,
+    };
+    let key = hive(location).open_subkey(subkey)?;
+
+    for (name, _) in key.enum_values().filter_map(Result::ok) {
+        // 只处理字符串值，get_value会同时读取REG_SZ和REG_EXPAND_SZ
+        let Ok(command) = key.get_value::<String, _>(&name) else {
+            continue;
+        };
+        let (path, args) = split_command(&command);
+        entries.push(SystemEntry {
+            id: format!("{}:{}", location.key(), name),
+            name: name.clone(),
+            location,
+            enabled: is_approved(location, &name),
+            can_disable: location.can_disable(),
+            source: name,
+            command,
+            launch: executable(path, args),
+            working_directory: String::new(),
+            icon: String::new(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn list_startup_folder(location: Location, entries: &mut Vec<SystemEntry>) -> io::Result<()> {
+    let (variable, folder) = match location {
+        Location::UserStartupFolder => ("APPDATA", "Startup"),
+        _ => ("ProgramData", "StartUp"),
+    };
+    let Some(base) = std::env::var_os(variable) else {
+        return Ok(());
+    };
+    let dir = PathBuf::from(base).join("Microsoft\\Windows\\Start Menu\\Programs").join(folder);
+
+    let read_dir = match fs::read_dir(&dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in read_dir {
+        let path = entry?.path();
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if !path.is_file() || file_name.eq_ignore_ascii_case("desktop.ini") {
+            continue;
+        }
+
+        let display_path = path.display().to_string();
+        let is_exe = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("exe"));
+        let launch = if is_exe {
+            executable(display_path.clone(), Vec::new())
+        } else {
+            // 快捷方式等文件交给系统按类型打开，参数和工作目录由快捷方式自身决定
+            Launch::Shell { command: format!("start \"\" \"{}\"", display_path), shell: crate::shell::Shell::Cmd }
+        };
+        entries.push(SystemEntry {
+            id: format!("{}:{}", location.key(), file_name),
+            name: path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+            location,
+            enabled: is_approved(location, &file_name),
+            can_disable: location.can_disable(),
+            source: display_path.clone(),
+            command: display_path,
+            launch,
+            working_directory: String::new(),
+            icon: String::new(),
+        });
+    }
+    Ok(())
+}
+
+// $XDG_CONFIG_HOME/autostart，未设置时为 ~/.config/autostart
+#[cfg(not(windows))]
+pub fn xdg_autostart_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("autostart"))
+}
+
+#[cfg(not(windows))]
+fn list_xdg_autostart(entries: &mut Vec<SystemEntry>) -> io::Result<()> {
+    let Some(dir) = xdg_autostart_dir() else {
+        return Ok(());
+    };
+    let read_dir = match fs::read_dir(&dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    for entry in read_dir {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "desktop") {
+            continue;
+        }
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::debug!("Failed to read {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if let Some(entry) = desktop_entry(&path, &content) {
+            entries.push(entry);
+        }
+    }
+    Ok(())
+}
+
+// 把.desktop文件转换为自启动项，不是应用程序或没有Exec时返回None
+#[cfg(not(windows))]
+fn desktop_entry(path: &Path, content: &str) -> Option<SystemEntry> {
+    let values = desktop_entry_values(content);
+    if values.get("Type").is_some_and(|kind| kind != "Application") {
+        return None;
+    }
+    let command = values.get("Exec")?.clone();
+    let mut args = split_exec(&command);
+    if args.is_empty() {
+        return None;
+    }
+    let program = args.remove(0);
+
+    let file_stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let is_true = |key: &str| values.get(key).is_some_and(|value| value == "true");
+    let enabled = !is_true("Hidden") && values.get("X-GNOME-Autostart-enabled").is_none_or(|value| value != "false");
+    // 图标可能是主题中的图标名，只保留文件路径
+    let icon = values.get("Icon").filter(|icon| Path::new(icon).is_absolute()).cloned().unwrap_or_default();
+
+    Some(SystemEntry {
+        id: format!("{}:{}", Location::XdgAutostart.key(), path.file_name().unwrap_or_default().to_string_lossy()),
+        name: values.get("Name").cloned().unwrap_or(file_stem),
+        location: Location::XdgAutostart,
+        source: path.display().to_string(),
+        command,
+        enabled,
+        can_disable: Location::XdgAutostart.can_disable(),
+        launch: executable(program, args),
+        working_directory: values.get("Path").cloned().unwrap_or_default(),
+        icon,
+    })
+}
+
+// [Desktop Entry]组中的键值，忽略本地化的键
+#[cfg(not(windows))]
+pub fn desktop_entry_values(content: &str) -> std::collections::HashMap<String, String> {
+    let mut values = std::collections::HashMap::new();
+    let mut in_group = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_group || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            values.entry(key.trim().to_string()).or_insert_with(|| unescape(value.trim()));
+        }
+    }
+    values
+}
+
+// 还原字符串值中的 \s、\n、\t、\r、\\ 转义
+#[cfg(not(windows))]
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => {
+                // 未知的转义保持原样
+                if other != '\\' {
+                    result.push('\\');
+                }
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+// 按desktop文件规范拆分Exec：引号内的反斜杠转义下一个字符，去掉%f、%U等字段代码
+#[cfg(not(windows))]
+fn split_exec(exec: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // 区分“没有参数”和“空参数”（例如 `""`）
+    let mut has_arg = false;
+    let mut in_quotes = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            '\\' if in_quotes => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ' ' | '\t' if !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            '%' => {
+                // %% 表示字面量 %，其他字段代码由启动器替换为文件或URL，这里直接去掉
+                if chars.next() == Some('%') {
+                    current.push('%');
+                    has_arg = true;
+                }
+            }
+            _ => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(current);
+    }
+    args
+}
+
+// 在[Desktop Entry]组中写入 Hidden=true，替换原有的Hidden
+#[cfg(not(windows))]
+fn set_hidden(content: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_group = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_group = trimmed == "[Desktop Entry]";
+            lines.push(line);
+            if in_group {
+                lines.push("Hidden=true");
+            }
+            continue;
+        }
+        if in_group && trimmed.split_once('=').is_some_and(|(key, _)| key.trim() == "Hidden") {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn run_commands_with_quoted_programs_are_split() {
+        assert_eq!(
+            split_command(r#""C:\Program Files\App\app.exe" --minimized"#),
+            (r"C:\Program Files\App\app.exe".to_string(), strings(&["--minimized"]))
+        );
+        assert_eq!(split_command(r#""C:\Tools\app.exe""#), (r"C:\Tools\app.exe".to_string(), Vec::new()));
+        // 缺少结尾引号时其余部分都是程序路径
+        assert_eq!(split_command(r#""C:\a b\app.exe"#), (r"C:\a b\app.exe".to_string(), Vec::new()));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn run_commands_without_quotes_are_split_after_exe() {
+        assert_eq!(
+            split_command(r"C:\Program Files\Foo\foo.exe /background"),
+            (r"C:\Program Files\Foo\foo.exe".to_string(), strings(&["/background"]))
+        );
+        assert_eq!(
+            split_command(r"%ProgramFiles%\App\APP.EXE -x"),
+            (r"%ProgramFiles%\App\APP.EXE".to_string(), strings(&["-x"]))
+        );
+        assert_eq!(
+            split_command(r"C:\Windows\system32\SecurityHealthSystray.exe"),
+            (r"C:\Windows\system32\SecurityHealthSystray.exe".to_string(), Vec::new())
+        );
+        assert_eq!(
+            split_command("rundll32.exe shell32.dll,Control_RunDLL"),
+            ("rundll32.exe".to_string(), strings(&["shell32.dll,Control_RunDLL"]))
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn run_commands_without_exe_are_split_at_whitespace() {
+        assert_eq!(split_command(r"C:\tools\run.bat arg"), (r"C:\tools\run.bat".to_string(), strings(&["arg"])));
+        // .exe之后不是空白时不作为程序路径的结尾
+        assert_eq!(
+            split_command(r"C:\my.exes\tool.exe -x"),
+            (r"C:\my.exes\tool.exe".to_string(), strings(&["-x"]))
+        );
+    }
+
+    #[cfg(not(windows))]
+    const BACKUP_TOOL: &str = include_str!("../tests/fixtures/system_startup/backup-tool.desktop");
+    #[cfg(not(windows))]
+    const NEXTCLOUD: &str = include_str!("../tests/fixtures/system_startup/nextcloud.desktop");
+
+    #[cfg(not(windows))]
+    #[test]
+    fn exec_field_codes_are_removed() {
+        assert_eq!(split_exec("/usr/bin/nextcloud --background %f"), strings(&["/usr/bin/nextcloud", "--background"]));
+        assert_eq!(split_exec("app %U --x"), strings(&["app", "--x"]));
+        assert_eq!(split_exec("app 100%%"), strings(&["app", "100%"]));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn exec_quotes_and_escapes_are_parsed() {
+        assert_eq!(
+            split_exec(r#""/opt/My App/app" --name "a b""#),
+            strings(&["/opt/My App/app", "--name", "a b"])
+        );
+        assert_eq!(
+            split_exec(r#"echo "say \"hi\"" "a\\b" "\$HOME""#),
+            strings(&["echo", "say \"hi\"", "a\\b", "$HOME"])
+        );
+        assert_eq!(split_exec("app \"\"  \t--x"), strings(&["app", "", "--x"]));
+        assert!(split_exec("  %f ").is_empty());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn string_escapes_are_unescaped() {
+        assert_eq!(unescape(r"a\sb"), "a b");
+        assert_eq!(unescape(r"1\n2\t3\r"), "1\n2\t3\r");
+        assert_eq!(unescape(r"C:\\dir"), r"C:\dir");
+        // 未知的转义和结尾的反斜杠保持原样
+        assert_eq!(unescape(r"\x\"), r"\x\");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn only_the_desktop_entry_group_is_read() {
+        let values = desktop_entry_values(BACKUP_TOOL);
+        assert_eq!(values["Name"], "Backup Tool");
+        assert_eq!(values["Name[zh_CN]"], "备份工具");
+        assert_eq!(values["Comment"], "Back up files\ton login");
+        assert_eq!(values["Exec"], r#""/opt/Backup Tool/backup" --config "/home/user/My Config/backup.ini" %U"#);
+        assert!(!values.contains_key("Desktop Action Pause"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn desktop_entry_becomes_a_startup_item() {
+        let path = Path::new("/home/user/.config/autostart/backup-tool.desktop");
+        let entry = desktop_entry(path, BACKUP_TOOL).unwrap();
+
+        assert_eq!(entry.id, "xdgAutostart:backup-tool.desktop");
+        assert_eq!(entry.name, "Backup Tool");
+        assert_eq!(entry.source, path.display().to_string());
+        assert!(entry.enabled);
+        assert!(entry.can_disable);
+        assert_eq!(entry.icon, "/opt/Backup Tool/icon.png");
+        assert_eq!(entry.working_directory, "/home/user");
+        match &entry.launch {
+            Launch::Executable { path, args, .. } => {
+                assert_eq!(path, "/opt/Backup Tool/backup");
+                assert_eq!(args.as_deref(), Some(&strings(&["--config", "/home/user/My Config/backup.ini"])[..]));
+            }
+            other => panic!("unexpected launch {:?}", other),
+        }
+        assert!(entry.is_program(Path::new("/opt/Backup Tool/backup")));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn gnome_disabled_entries_are_not_enabled() {
+        let entry = desktop_entry(Path::new("nextcloud.desktop"), NEXTCLOUD).unwrap();
+        assert!(!entry.enabled);
+        // 主题图标名不是文件路径
+        assert_eq!(entry.icon, "");
+        assert_eq!(entry.name, "Nextcloud");
+
+        let hidden = BACKUP_TOOL.replace("Terminal=false", "Hidden=true");
+        assert!(!desktop_entry(Path::new("backup-tool.desktop"), &hidden).unwrap().enabled);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn non_applications_are_skipped() {
+        assert!(desktop_entry(Path::new("link.desktop"), "[Desktop Entry]\nType=Link\nExec=app\n").is_none());
+        assert!(desktop_entry(Path::new("empty.desktop"), "[Desktop Entry]\nName=Empty\n").is_none());
+        assert!(desktop_entry(Path::new("blank.desktop"), "[Desktop Entry]\nExec=%f\n").is_none());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn set_hidden_replaces_an_existing_value() {
+        let content = "[Desktop Entry]\nName=A\nHidden = false\nExec=a\n";
+        assert_eq!(set_hidden(content), "[Desktop Entry]\nHidden=true\nName=A\nExec=a\n");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn set_hidden_adds_the_value_to_the_desktop_entry_group() {
+        let hidden = set_hidden(NEXTCLOUD);
+        assert!(hidden.starts_with("[Desktop Entry]\nHidden=true\nName=Nextcloud\n"), "{}", hidden);
+        assert_eq!(hidden.matches("Hidden=").count(), 1);
+
+        // 其他组中的同名键不受影响
+        let content = "[Desktop Entry]\nExec=a\n\n[Desktop Action X]\nHidden=false\n";
+        assert_eq!(set_hidden(content), "[Desktop Entry]\nHidden=true\nExec=a\n\n[Desktop Action X]\nHidden=false\n");
+        assert!(!desktop_entry(Path::new("a.desktop"), &set_hidden(content)).unwrap().enabled);
+    }
+}
@@ -0,0 +1,261 @@
+// 计划任务定义的类型化模型，对应任务计划程序XML架构中常用的部分
+//
+// 只包含登录触发器和启动程序操作，足以描述EasiStartup自身的计划任务。
+
+use super::xml::{self, Writer};
+use std::time::Duration;
+
+const NAMESPACE: &str = "http://schemas.microsoft.com/windows/2004/02/mit/task";
+// 主体和操作通过此ID关联
+const PRINCIPAL_ID: &str = "Author";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskDefinition {
+    pub description: Option<String>,
+    pub triggers: Vec<Trigger>,
+    pub principal: Principal,
+    pub settings: Settings,
+    pub actions: Vec<ExecAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trigger {
+    // 用户登录时；user_id为None表示任意用户登录
+    Logon { user_id: Option<String>, delay: Option<Duration> },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RunLevel {
+    #[default]
+    LeastPrivilege,
+    HighestAvailable,
+}
+
+// 运行任务的账户；user_id为None时使用注册任务的用户
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Principal {
+    pub user_id: Option<String>,
+    pub run_level: RunLevel,
+}
+
+// 任务已在运行时再次触发会被忽略（IgnoreNew）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub disallow_start_if_on_batteries: bool,
+    pub stop_if_going_on_batteries: bool,
+    pub allow_hard_terminate: bool,
+    pub start_when_available: bool,
+    pub run_only_if_network_available: bool,
+    pub allow_start_on_demand: bool,
+    pub enabled: bool,
+    pub hidden: bool,
+    pub wake_to_run: bool,
+    // 运行时间上限，None表示不限制
+    pub execution_time_limit: Option<Duration>,
+    // 0最高，10最低
+    pub priority: u8,
+}
+
+// 默认值允许使用电池时运行且不限制运行时间
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            disallow_start_if_on_batteries: false,
+            stop_if_going_on_batteries: false,
+            allow_hard_terminate: true,
+            start_when_available: false,
+            run_only_if_network_available: false,
+            allow_start_on_demand: true,
+            enabled: true,
+            hidden: false,
+            wake_to_run: false,
+            execution_time_limit: None,
+            priority: 7,
+        }
+    }
+}
+
+// 启动程序
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecAction {
+    pub command: String,
+    // 拼接好的命令行参数
+    pub arguments: Option<String>,
+    pub working_directory: Option<String>,
+}
+
+// ISO 8601时长，例如PT30S
+fn duration(value: Duration) -> String {
+    format!("PT{}S", value.as_secs())
+}
+
+fn boolean(value: bool) -> &'static str {
+    if value { "true" } else { "false" }
+}
+
+impl Trigger {
+    fn write(&self, writer: &mut Writer) {
+        let Trigger::Logon { user_id, delay } = self;
+
+        writer.start("LogonTrigger", &[]);
+        writer.element("Enabled", "true");
+        if let Some(user_id) = user_id {
+            writer.element("UserId", user_id);
+        }
+        if let Some(delay) = delay {
+            writer.element("Delay", &duration(*delay));
+        }
+        writer.end();
+    }
+}
+
+impl Settings {
+    fn write(&self, writer: &mut Writer) {
+        writer.start("Settings", &[]);
+        writer.element("MultipleInstancesPolicy", "IgnoreNew");
+        writer.element("DisallowStartIfOnBatteries", boolean(self.disallow_start_if_on_batteries));
+        writer.element("StopIfGoingOnBatteries", boolean(self.stop_if_going_on_batteries));
+        writer.element("AllowHardTerminate", boolean(self.allow_hard_terminate));
+        writer.element("StartWhenAvailable", boolean(self.start_when_available));
+        writer.element("RunOnlyIfNetworkAvailable", boolean(self.run_only_if_network_available));
+        writer.start("IdleSettings", &[]);
+        writer.element("StopOnIdleEnd", "true");
+        writer.element("RestartOnIdle", "false");
+        writer.end();
+        writer.element("AllowStartOnDemand", boolean(self.allow_start_on_demand));
+        writer.element("Enabled", boolean(self.enabled));
+        writer.element("Hidden", boolean(self.hidden));
+        writer.element("RunOnlyIfIdle", "false");
+        writer.element("WakeToRun", boolean(self.wake_to_run));
+        writer.element("ExecutionTimeLimit", &self.execution_time_limit.map_or("PT0S".to_string(), duration));
+        writer.element("Priority", &self.priority.min(10).to_string());
+        writer.end();
+    }
+}
+
+impl TaskDefinition {
+    // 在用户登录时以指定权限运行单个程序的任务
+    pub fn logon(action: ExecAction, run_level: RunLevel, delay: Option<Duration>) -> TaskDefinition {
+        TaskDefinition {
+            description: None,
+            triggers: vec![Trigger::Logon { user_id: None, delay }],
+            principal: Principal { user_id: None, run_level },
+            settings: Settings::default(),
+            actions: vec![action],
+        }
+    }
+
+    // 生成声明为UTF-16的XML文本
+    pub fn to_xml(&self) -> String {
+        let mut writer = Writer::new();
+        writer.start("Task", &[("version", "1.2"), ("xmlns", NAMESPACE)]);
+
+        if let Some(description) = &self.description {
+            writer.start("RegistrationInfo", &[]);
+            writer.element("Description", description);
+            writer.end();
+        }
+
+        writer.start("Triggers", &[]);
+        for trigger in &self.triggers {
+            trigger.write(&mut writer);
+        }
+        writer.end();
+
+        writer.start("Principals", &[]);
+        writer.start("Principal", &[("id", PRINCIPAL_ID)]);
+        if let Some(user_id) = &self.principal.user_id {
+            writer.element("UserId", user_id);
+        }
+        let run_level = match self.principal.run_level {
+            RunLevel::LeastPrivilege => "LeastPrivilege",
+            RunLevel::HighestAvailable => "HighestAvailable",
+        };
+        writer.element("RunLevel", run_level);
+        writer.end();
+        writer.end();
+
+        self.settings.write(&mut writer);
+
+        writer.start("Actions", &[("Context", PRINCIPAL_ID)]);
+        for action in &self.actions {
+            writer.start("Exec", &[]);
+            writer.element("Command", &action.command);
+            if let Some(arguments) = action.arguments.as_deref().filter(|arguments| !arguments.is_empty()) {
+                writer.element("Arguments", arguments);
+            }
+            if let Some(directory) = &action.working_directory {
+                writer.element("WorkingDirectory", directory);
+            }
+            writer.end();
+        }
+        writer.end();
+
+        writer.finish()
+    }
+
+    // 写入文件时使用的字节，与XML声明的编码一致
+    pub fn to_utf16(&self) -> Vec<u8> {
+        xml::encode_utf16(&self.to_xml())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> TaskDefinition {
+        let action = ExecAction {
+            command: "C:\\Program Files\\App\\app.exe".to_string(),
+            arguments: Some("--name \"a b\"".to_string()),
+            working_directory: Some("C:\\Work".to_string()),
+        };
+        TaskDefinition::logon(action, RunLevel::LeastPrivilege, Some(Duration::from_secs(30)))
+    }
+
+    #[test]
+    fn utf16_output_is_bom_and_little_endian() {
+        let xml = task().to_xml();
+        let bytes = task().to_utf16();
+
+        assert_eq!(&bytes[..2], &[0xFF, 0xFE]);
+        assert_eq!(&bytes[2..6], &[b'<', 0, b'?', 0]);
+        let units: Vec<u16> = bytes[2..].chunks(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+        assert_eq!(String::from_utf16(&units).unwrap(), xml);
+    }
+
+    #[test]
+    fn non_ascii_text_is_encoded_as_utf16() {
+        let task = TaskDefinition { description: Some("自启动 😀".to_string()), ..task() };
+        let bytes = task.to_utf16();
+
+        let mut expected = Vec::new();
+        for unit in "自启动 😀".encode_utf16() {
+            expected.extend(unit.to_le_bytes());
+        }
+        assert!(bytes.windows(expected.len()).any(|window| window == expected));
+        assert!(expected.ends_with(&[0x3D, 0xD8, 0x00, 0xDE]));
+    }
+
+    #[test]
+    fn values_read_back_from_xml() {
+        let xml = task().to_xml();
+        let text = |path: &[&str]| xml::text(&xml, path);
+
+        assert_eq!(text(&["Triggers", "LogonTrigger", "Delay"]).as_deref(), Some("PT30S"));
+        assert_eq!(text(&["Principals", "Principal", "RunLevel"]).as_deref(), Some("LeastPrivilege"));
+        assert_eq!(text(&["Settings", "MultipleInstancesPolicy"]).as_deref(), Some("IgnoreNew"));
+        assert_eq!(text(&["Settings", "ExecutionTimeLimit"]).as_deref(), Some("PT0S"));
+        assert_eq!(text(&["Actions", "Exec", "Command"]).as_deref(), Some("C:\\Program Files\\App\\app.exe"));
+        assert_eq!(text(&["Actions", "Exec", "Arguments"]).as_deref(), Some("--name \"a b\""));
+        assert_eq!(text(&["Actions", "Exec", "WorkingDirectory"]).as_deref(), Some("C:\\Work"));
+        assert_eq!(text(&["RegistrationInfo", "Description"]), None);
+    }
+
+    #[test]
+    fn empty_arguments_are_omitted() {
+        let mut task = task();
+        task.actions[0].arguments = Some(String::new());
+        assert_eq!(xml::text(&task.to_xml(), &["Actions", "Exec", "Arguments"]), None);
+    }
+}
@@ -0,0 +1,136 @@
+// Windows计划任务
+//
+// 任务定义由TaskDefinition生成XML，写入UTF-16编码的临时文件后通过schtasks导入。
+// 不依赖schtasks的本地化输出文本：任务是否存在按 /query /fo csv 列出的任务名判断，
+// 任务定义通过 /query /xml 读取，运行状态按 /query /fo csv /v 输出中固定的列位置读取。
+
+use serde::Serialize;
+
+pub mod definition;
+pub mod xml;
+#[cfg(windows)]
+mod schtasks;
+
+#[cfg(windows)]
+pub use schtasks::Schtasks;
+
+// /query /fo csv /v 输出中上次运行时间和上次结果所在的列
+const LAST_RUN_COLUMN: usize = 5;
+const LAST_RESULT_COLUMN: usize = 6;
+// SCHED_S_TASK_HAS_NOT_RUN：任务从未运行
+const HAS_NOT_RUN: i64 = 0x41303;
+
+// 计划任务的定义和运行状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    pub enabled: bool,
+    pub command: String,
+    pub arguments: String,
+    // 以最高权限运行
+    pub highest_privileges: bool,
+    // 上次运行时间，按系统区域设置格式化；从未运行或无法获取时为None
+    pub last_run: Option<String>,
+    // 上次运行的结果码，0表示成功
+    pub last_result: Option<i64>,
+}
+
+impl TaskInfo {
+    // 从 /query /xml 输出的任务定义中读取，运行状态需另外通过with_status补充
+    pub fn from_xml(task: &str) -> TaskInfo {
+        let text = |path: &[&str]| xml::text(task, path);
+        TaskInfo {
+            // 未指定时任务默认启用
+            enabled: text(&["Settings", "Enabled"]).is_none_or(|value| value != "false"),
+            command: text(&["Actions", "Exec", "Command"]).unwrap_or_default(),
+            arguments: text(&["Actions", "Exec", "Arguments"]).unwrap_or_default(),
+            highest_privileges: text(&["Principals", "Principal", "RunLevel"])
+                .is_some_and(|level| level == "HighestAvailable"),
+            last_run: None,
+            last_result: None,
+        }
+    }
+
+    // 从 /query /fo csv /v /nh 的输出中补充上次运行时间和结果
+    pub fn with_status(mut self, csv: &str) -> TaskInfo {
+        let Some(fields) = csv.lines().find(|line| !line.trim().is_empty()).map(csv_fields) else {
+            return self;
+        };
+
+        let result = fields.get(LAST_RESULT_COLUMN).and_then(|value| value.trim().parse::<i64>().ok());
+        if result != Some(HAS_NOT_RUN) {
+            self.last_result = result;
+            self.last_run = fields.get(LAST_RUN_COLUMN).map(|value| value.trim().to_string());
+        }
+        self
+    }
+}
+
+// /query /fo csv /nh 列出的任务中是否有名为name的任务
+//
+// 第一列是带路径的任务名，根目录下的任务以\开头；任务名不区分大小写。
+pub fn list_contains(csv: &str, name: &str) -> bool {
+    let name = name.trim_start_matches('\\');
+    csv.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| csv_fields(line).into_iter().next())
+        .any(|task| task.trim_start_matches('\\').eq_ignore_ascii_case(name))
+}
+
+// 拆分一行CSV，字段中的""表示一个引号
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_list_is_matched_by_full_name() {
+        let csv = concat!(
+            "\"\\EasiStartup_AdminTask\",\"N/A\",\"Ready\"\r\n",
+            "\r\n",
+            "\"\\Microsoft\\Windows\\Backup\",\"2026/10/18 3:00:00\",\"Ready\"\r\n",
+        );
+        assert!(list_contains(csv, "EasiStartup_AdminTask"));
+        assert!(list_contains(csv, "\\easistartup_admintask"));
+        assert!(list_contains(csv, "Microsoft\\Windows\\Backup"));
+        assert!(!list_contains(csv, "Backup"));
+        assert!(!list_contains(csv, "EasiStartup"));
+        assert!(!list_contains("", "EasiStartup_AdminTask"));
+    }
+
+    #[test]
+    fn status_columns_are_read_by_position() {
+        let csv = "\"HOST\",\"\\EasiStartup_AdminTask\",\"N/A\",\"Ready\",\"Interactive only\",\"2026/10/17 9:00:00\",\"1\"\r\n";
+        let info = TaskInfo::default().with_status(csv);
+        assert_eq!(info.last_run.as_deref(), Some("2026/10/17 9:00:00"));
+        assert_eq!(info.last_result, Some(1));
+
+        let never_run = csv.replace("\"1\"", &format!("\"{}\"", HAS_NOT_RUN));
+        assert_eq!(TaskInfo::default().with_status(&never_run), TaskInfo::default());
+    }
+
+    #[test]
+    fn quoted_csv_fields_are_unescaped() {
+        assert_eq!(csv_fields(r#""a,b","say ""hi""",c"#), vec!["a,b", r#"say "hi""#, "c"]);
+    }
+}
@@ -0,0 +1,164 @@
+// 通过schtasks命令管理计划任务
+
+use super::definition::TaskDefinition;
+use super::{list_contains, TaskInfo};
+use crate::error::AppError;
+use crate::i18n::{trf, Message};
+use crate::runner::{CommandRunner, Output};
+use log::warn;
+use std::fs;
+use uuid::Uuid;
+
+pub struct Schtasks<R> {
+    runner: R,
+}
+
+impl<R: CommandRunner> Schtasks<R> {
+    pub fn new(runner: R) -> Schtasks<R> {
+        Schtasks { runner }
+    }
+
+    fn run(&self, args: &[&str], stdin: Option<&[u8]>) -> Result<Output, AppError> {
+        self.runner
+            .run("schtasks", args, stdin)
+            .map_err(|e| AppError::TaskScheduler(trf(Message::SchtasksSpawnFailed, &[&e])))
+    }
+
+    // 任务是否存在
+    //
+    // 任务不存在和无权访问时 /query /tn 的退出码相同，因此列出所有任务后按名称查找，
+    // 列出失败时返回错误。
+    pub fn exists(&self, name: &str) -> Result<bool, AppError> {
+        let output = self.run(&["/query", "/fo", "csv", "/nh"], None)?;
+        if !output.success() {
+            return Err(AppError::TaskScheduler(trf(Message::TaskListFailed, &[&output.stderr.trim()])));
+        }
+        Ok(list_contains(&output.stdout, name))
+    }
+
+    // 按任务定义创建任务，已存在时覆盖
+    //
+    // schtasks只能从文件读取XML，因此先写入临时文件，导入后删除。
+    pub fn create(&self, name: &str, task: &TaskDefinition) -> Result<(), AppError> {
+        let path = std::env::temp_dir().join(format!("easistartup-task-{}.xml", Uuid::new_v4()));
+        fs::write(&path, task.to_utf16())
+            .map_err(|e| AppError::TaskScheduler(trf(Message::TaskXmlWriteFailed, &[&e])))?;
+
+        let path_arg = path.to_string_lossy();
+        let output = self.run(&["/create", "/tn", name, "/xml", &path_arg, "/f"], None);
+        if let Err(e) = fs::remove_file(&path) {
+            warn!("Failed to remove temporary task file {}: {}", path.display(), e);
+        }
+
+        let output = output?;
+        if !output.success() {
+            return Err(AppError::TaskScheduler(trf(Message::TaskCreateFailed, &[&output.stderr.trim()])));
+        }
+        Ok(())
+    }
+
+    // 删除任务，任务本就不存在时视为成功
+    pub fn delete(&self, name: &str) -> Result<(), AppError> {
+        let output = self.run(&["/delete", "/tn", name, "/f"], None)?;
+        // 退出码同样无法区分任务不存在和其他错误，失败后确认任务是否还在
+        if output.success() || !self.exists(name)? {
+            return Ok(());
+        }
+        Err(AppError::TaskScheduler(trf(Message::TaskDeleteFailed, &[&output.stderr.trim()])))
+    }
+
+    // 读取任务定义和运行状态，任务不存在时返回None
+    pub fn query(&self, name: &str) -> Result<Option<TaskInfo>, AppError> {
+        let definition = self.run(&["/query", "/tn", name, "/xml"], None)?;
+        if !definition.success() {
+            return Ok(None);
+        }
+        let info = TaskInfo::from_xml(&definition.stdout);
+
+        let status = self.run(&["/query", "/tn", name, "/fo", "csv", "/v", "/nh"], None)?;
+        if !status.success() {
+            warn!("Failed to query the run status of task {}: {}", name, status.stderr.trim());
+            return Ok(Some(info));
+        }
+        Ok(Some(info.with_status(&status.stdout)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::recording::RecordingRunner;
+    use crate::task_scheduler::definition::{ExecAction, RunLevel};
+
+    const TASK_LIST: &str = "\"\\EasiStartup_AdminTask\",\"N/A\",\"Ready\"\r\n";
+
+    fn output(code: i32, stdout: &str) -> Output {
+        Output { code: Some(code), stdout: stdout.to_string(), stderr: String::new() }
+    }
+
+    // 列出任务时返回list，其余命令以code结束
+    fn schtasks(code: i32, list: &'static str) -> Schtasks<RecordingRunner> {
+        Schtasks::new(RecordingRunner::responding(move |_, args| match args {
+            ["/query", "/fo", "csv", "/nh"] => output(0, list),
+            _ => output(code, ""),
+        }))
+    }
+
+    #[test]
+    fn exists_looks_up_the_task_list() {
+        let tasks = schtasks(0, TASK_LIST);
+        assert!(tasks.exists("EasiStartup_AdminTask").unwrap());
+        assert!(!tasks.exists("Other").unwrap());
+        assert_eq!(tasks.runner.calls(), vec!["schtasks /query /fo csv /nh"; 2]);
+    }
+
+    #[test]
+    fn exists_fails_when_tasks_cannot_be_listed() {
+        let tasks = Schtasks::new(RecordingRunner::responding(|_, _| output(1, "")));
+        assert!(matches!(tasks.exists("EasiStartup_AdminTask"), Err(AppError::TaskScheduler(_))));
+    }
+
+    #[test]
+    fn create_imports_a_temporary_xml_file() {
+        let tasks = schtasks(0, "");
+        let action = ExecAction { command: "app.exe".to_string(), arguments: None, working_directory: None };
+        let task = TaskDefinition::logon(action, RunLevel::HighestAvailable, None);
+        tasks.create("EasiStartup_AdminTask", &task).unwrap();
+
+        let calls = tasks.runner.calls();
+        assert_eq!(calls.len(), 1);
+        let path = calls[0]
+            .strip_prefix("schtasks /create /tn EasiStartup_AdminTask /xml ")
+            .and_then(|rest| rest.strip_suffix(" /f"))
+            .unwrap();
+        assert!(!std::path::Path::new(path).exists());
+    }
+
+    #[test]
+    fn delete_succeeds_when_the_task_is_gone() {
+        let deleted = schtasks(0, TASK_LIST);
+        deleted.delete("EasiStartup_AdminTask").unwrap();
+        assert_eq!(deleted.runner.calls(), vec!["schtasks /delete /tn EasiStartup_AdminTask /f"]);
+
+        // 删除失败但任务已不存在
+        let missing = schtasks(1, "");
+        missing.delete("EasiStartup_AdminTask").unwrap();
+        assert_eq!(
+            missing.runner.calls(),
+            vec!["schtasks /delete /tn EasiStartup_AdminTask /f", "schtasks /query /fo csv /nh"]
+        );
+    }
+
+    #[test]
+    fn delete_fails_when_the_task_remains() {
+        let tasks = schtasks(1, TASK_LIST);
+        assert!(matches!(tasks.delete("EasiStartup_AdminTask"), Err(AppError::TaskScheduler(_))));
+    }
+
+    #[test]
+    fn query_returns_none_for_missing_tasks() {
+        let tasks = schtasks(1, "");
+        assert_eq!(tasks.query("EasiStartup_AdminTask").unwrap(), None);
+        assert_eq!(tasks.runner.calls(), vec!["schtasks /query /tn EasiStartup_AdminTask /xml"]);
+    }
+}
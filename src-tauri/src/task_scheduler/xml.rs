@@ -0,0 +1,207 @@
+// 任务XML的生成和读取
+//
+// 读取只处理schtasks输出的任务定义：没有命名空间前缀，也没有同名元素嵌套。
+
+// 逐行生成带缩进的XML，元素按start/end配对
+pub struct Writer {
+    output: String,
+    open: Vec<&'static str>,
+}
+
+impl Writer {
+    // 声明为UTF-16，写入文件时需用encode_utf16编码
+    pub fn new() -> Writer {
+        Writer { output: String::from("<?xml version=\"1.0\" encoding=\"UTF-16\"?>\n"), open: Vec::new() }
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.open.len() {
+            self.output.push_str("  ");
+        }
+    }
+
+    // 开始一个包含子元素的元素
+    pub fn start(&mut self, name: &'static str, attributes: &[(&str, &str)]) {
+        self.indent();
+        self.output.push('<');
+        self.output.push_str(name);
+        for (key, value) in attributes {
+            self.output.push_str(&format!(" {}=\"{}\"", key, escape(value)));
+        }
+        self.output.push_str(">\n");
+        self.open.push(name);
+    }
+
+    // 结束最近开始的元素
+    pub fn end(&mut self) {
+        if let Some(name) = self.open.pop() {
+            self.indent();
+            self.output.push_str(&format!("</{}>\n", name));
+        }
+    }
+
+    // 只包含文本的元素
+    pub fn element(&mut self, name: &str, text: &str) {
+        self.indent();
+        self.output.push_str(&format!("<{}>{}</{}>\n", name, escape(text), name));
+    }
+
+    // 结束所有未结束的元素并返回结果
+    pub fn finish(mut self) -> String {
+        while !self.open.is_empty() {
+            self.end();
+        }
+        self.output
+    }
+}
+
+// 转义文本和属性值中的特殊字符，丢弃XML 1.0不允许出现的字符
+pub fn escape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            '\t' | '\n' | '\r' => result.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => {}
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+// 编码为带BOM的UTF-16LE，任务计划程序导入的XML文件使用此编码
+pub fn encode_utf16(xml: &str) -> Vec<u8> {
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(xml.encode_utf16().flat_map(u16::to_le_bytes));
+    bytes
+}
+
+// 按路径查找第一个匹配元素的文本，例如 ["Actions", "Exec", "Command"]
+pub fn text(xml: &str, path: &[&str]) -> Option<String> {
+    let mut content = xml;
+    for name in path {
+        content = inner(content, name)?;
+    }
+    Some(unescape(content.trim()))
+}
+
+// 第一个名为name的元素的内容，自闭合元素的内容为空
+fn inner<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{}", name);
+    let mut from = 0;
+    loop {
+        let after = from + xml[from..].find(&open)? + open.len();
+        // 跳过名称以name开头的其他元素，例如Exec和ExecutionTimeLimit
+        if !xml[after..].starts_with(['>', '/', ' ', '\t', '\r', '\n']) {
+            from = after;
+            continue;
+        }
+
+        let open_end = after + xml[after..].find('>')?;
+        if xml[..open_end].ends_with('/') {
+            return Some("");
+        }
+        let start = open_end + 1;
+        let end = start + xml[start..].find(&format!("</{}>", name))?;
+        return Some(&xml[start..end]);
+    }
+}
+
+// 还原字符引用和预定义实体，无法识别的原样保留
+pub fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(index) = rest.find('&') {
+        result.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                reference => {
+                    let code = match reference.strip_prefix("#x") {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => reference.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_unescape_round_trip() {
+        for value in [
+            "plain",
+            r#"C:\R&D <Tools>\"Easi"\app.exe"#,
+            "it's > that & this",
+            "&amp; already escaped",
+            "tab\tnewline\n中文 😀",
+        ] {
+            assert_eq!(unescape(&escape(value)), value);
+        }
+    }
+
+    #[test]
+    fn escape_drops_invalid_characters() {
+        assert_eq!(escape("a\u{0}b\u{1b}c\u{fffe}d"), "abcd");
+    }
+
+    #[test]
+    fn unescape_decodes_character_references() {
+        assert_eq!(unescape("&#65;&#x4E2D;&#x1F600;"), "A中😀");
+        // 无法识别的引用原样保留
+        assert_eq!(unescape("a & b &unknown; &#xZZ; &"), "a & b &unknown; &#xZZ; &");
+    }
+
+    #[test]
+    fn text_round_trips_through_writer() {
+        let value = r#"<"a" & 'b'>"#;
+        let mut writer = Writer::new();
+        writer.start("Task", &[("version", "1.2")]);
+        writer.start("Actions", &[]);
+        writer.start("Exec", &[]);
+        writer.element("Command", value);
+        writer.end();
+        writer.end();
+        writer.element("ExecutionTimeLimit", "PT0S");
+        let xml = writer.finish();
+
+        assert_eq!(text(&xml, &["Actions", "Exec", "Command"]).as_deref(), Some(value));
+        assert_eq!(text(&xml, &["ExecutionTimeLimit"]).as_deref(), Some("PT0S"));
+        assert_eq!(text(&xml, &["Actions", "Missing"]), None);
+    }
+
+    #[test]
+    fn self_closing_elements_are_empty() {
+        let xml = "<Task><Actions><Exec><Arguments/></Exec></Actions></Task>";
+        assert_eq!(text(xml, &["Actions", "Exec", "Arguments"]).as_deref(), Some(""));
+    }
+}
@@ -0,0 +1,19 @@
+// 后端命令返回的错误类型定义
+export type AppErrorCode =
+  | 'IO'
+  | 'PARSE'
+  | 'REGISTRY'
+  | 'TASK_SCHEDULER'
+  | 'SYSTEMD'
+  | 'ELEVATION'
+  | 'NOT_FOUND'
+  | 'VALIDATION'
+  | 'LAUNCH_SPAWN'
+  | 'LAUNCH_EXIT'
+  | 'UNSUPPORTED'
+  | 'INTERNAL';
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+}
@@ -1,8 +1,13 @@
+// 后端消息支持的语言
+export type AppLanguage = 'zh-CN' | 'en-US';
+
 // 应用设置类型定义
 export interface AppSettings {
   auto_startup_enabled: boolean;
   auto_startup_as_admin: boolean;
   exit_after_startup: boolean;
+  // 为空时跟随系统
+  language?: AppLanguage | null;
 }
 
 // 前端设置类型定义
@@ -10,6 +15,7 @@ export interface FrontendSettings {
   autoStart: boolean;
   runAsAdmin: boolean;
   exitAfterStartup: boolean;
+  language: AppLanguage | null;
 }
 
 // 启动项类型定义
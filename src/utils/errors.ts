@@ -0,0 +1,27 @@
+import type { AppError, AppErrorCode } from '../types/errors';
+
+/**
+ * 判断是否为后端命令返回的错误
+ */
+export function isAppError(error: unknown): error is AppError {
+  return typeof error === 'object' && error !== null
+    && typeof (error as AppError).code === 'string'
+    && typeof (error as AppError).message === 'string';
+}
+
+/**
+ * 获取错误代码，非后端错误时返回undefined
+ */
+export function errorCode(error: unknown): AppErrorCode | undefined {
+  return isAppError(error) ? error.code : undefined;
+}
+
+/**
+ * 获取给用户看的错误说明
+ */
+export function errorMessage(error: unknown): string {
+  if (isAppError(error)) {
+    return error.message;
+  }
+  return error instanceof Error ? error.message : String(error);
+}
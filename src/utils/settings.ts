@@ -9,6 +9,7 @@ export function mapBackendToFrontend(backendSettings: AppSettings): FrontendSett
     autoStart: backendSettings.auto_startup_enabled,
     runAsAdmin: backendSettings.auto_startup_as_admin,
     exitAfterStartup: backendSettings.exit_after_startup,
+    language: backendSettings.language ?? null,
   };
 }
 
@@ -20,6 +21,7 @@ export function mapFrontendToBackend(frontendSettings: FrontendSettings): AppSet
     auto_startup_enabled: frontendSettings.autoStart,
     auto_startup_as_admin: frontendSettings.runAsAdmin,
     exit_after_startup: frontendSettings.exitAfterStartup,
+    language: frontendSettings.language,
   };
 }
 